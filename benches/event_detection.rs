@@ -0,0 +1,73 @@
+#![allow(clippy::needless_return)]
+
+use std::io::{BufReader, Read};
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+
+use quake_log_parser_lib::{
+    implementation::log_event::is_known_log_line,
+    lib::factory
+};
+
+const SAMPLE_LOG_FILE: &str = "sample_log.log";
+const DEFAULT_BENCH_LOG_MB: u64 = 16;
+
+/// The sample log repeated up to `size` bytes, streamed so multi-GB runs need no memory.
+struct RepeatedLog {
+    log: Vec<u8>,
+    position: usize,
+    remaining: u64
+}
+
+impl Read for RepeatedLog {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+
+        let chunk = buf.len().min(self.log.len() - self.position).min(self.remaining as usize);
+        buf[..chunk].copy_from_slice(&self.log[self.position..self.position + chunk]);
+
+        self.position = (self.position + chunk) % self.log.len();
+        self.remaining -= chunk as u64;
+
+        return Ok(chunk);
+    }
+}
+
+fn sample_log() -> Vec<u8> {
+    let mut log = std::fs::read(SAMPLE_LOG_FILE).unwrap();
+    if !log.ends_with(b"\n") {
+        log.push(b'\n');
+    }
+    return log;
+}
+
+fn event_detection(c: &mut Criterion) {
+
+    let log = String::from_utf8(sample_log()).unwrap();
+
+    let mut group = c.benchmark_group("event_detection");
+    group.throughput(Throughput::Bytes(log.len() as u64));
+    group.bench_function("sample_log_lines", |b| b.iter(|| log.lines().filter(|line| is_known_log_line(line)).count()));
+    group.finish();
+}
+
+/// `BENCH_LOG_MB=4096 cargo bench --bench event_detection` parses a 4 GB log per iteration.
+fn parse_throughput(c: &mut Criterion) {
+
+    let log_mb: u64 = std::env::var("BENCH_LOG_MB").ok().and_then(|log_mb| log_mb.parse().ok()).unwrap_or(DEFAULT_BENCH_LOG_MB);
+    let log_size = log_mb * 1024 * 1024;
+    let log = sample_log();
+
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+
+    let mut group = c.benchmark_group("parse_log");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(log_size));
+    group.bench_function(format!("repeated_sample_log_{}_mb", log_mb), |b| b.iter(|| {
+        let reader = BufReader::new(RepeatedLog { log: log.clone(), position: 0, remaining: log_size });
+        runtime.block_on(factory().parse_log(Box::new(reader), None)).unwrap()
+    }));
+    group.finish();
+}
+
+criterion_group!(benches, event_detection, parse_throughput);
+criterion_main!(benches);
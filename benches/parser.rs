@@ -0,0 +1,116 @@
+#![allow(clippy::needless_return)]
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+use quake_log_parser_lib::{
+    config::{
+        config::ConfigValue,
+        dynamic_config::{CONFIG, ConfigParameter}
+    },
+    implementation::{
+        log_event::is_known_log_line,
+        log_reader::log_bytes_reader,
+        synthetic_log::SyntheticLog
+    },
+    lib::factory
+};
+
+const SEED: u64 = 2009;
+
+fn synthetic_log(players: usize) -> String {
+    return SyntheticLog::new(SEED)
+        .with_matches(40)
+        .with_players(players)
+        .with_kills_per_match(200)
+        .with_renames(0.02)
+        .with_disconnects(0.01)
+        .with_junk_lines(0.01)
+        .generate().0;
+}
+
+fn set_config_parameter(parameter: ConfigParameter, value: ConfigValue) {
+    CONFIG.with(|config| {
+        config.borrow_mut().set_parameter(parameter, value);
+    });
+}
+
+fn event_detection(c: &mut Criterion) {
+
+    let log = synthetic_log(8);
+
+    let mut group = c.benchmark_group("synthetic_event_detection");
+    group.throughput(Throughput::Bytes(log.len() as u64));
+    group.bench_function("known_lines", |b| b.iter(|| log.lines().filter(|line| is_known_log_line(line)).count()));
+    group.finish();
+}
+
+/// Whole parse of a log into its report, as players per match grow.
+fn parse_log(c: &mut Criterion) {
+
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+
+    let mut group = c.benchmark_group("synthetic_parse_log");
+    group.sample_size(20);
+
+    for players in [4, 16, 64] {
+
+        let log = synthetic_log(players).into_bytes();
+
+        group.throughput(Throughput::Bytes(log.len() as u64));
+        group.bench_with_input(BenchmarkId::new("players", players), &log, |b, log| b.iter(|| {
+            runtime.block_on(factory().parse_log(log_bytes_reader(log.clone()).unwrap(), None)).unwrap()
+        }));
+    }
+
+    group.finish();
+}
+
+/// Kill bookkeeping with every optional per kill stat enabled.
+fn parse_log_with_stats(c: &mut Criterion) {
+
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let log = synthetic_log(16).into_bytes();
+
+    set_config_parameter(ConfigParameter::ShowDeathCauses, ConfigValue::Bool(true));
+    set_config_parameter(ConfigParameter::ShowPlayerStats, ConfigValue::Bool(true));
+    set_config_parameter(ConfigParameter::ShowRatingDeltas, ConfigValue::Bool(true));
+
+    let mut group = c.benchmark_group("synthetic_parse_log_with_stats");
+    group.sample_size(20);
+    group.throughput(Throughput::Bytes(log.len() as u64));
+    group.bench_function("players/16", |b| b.iter(|| {
+        runtime.block_on(factory().parse_log(log_bytes_reader(log.clone()).unwrap(), None)).unwrap()
+    }));
+    group.finish();
+
+    set_config_parameter(ConfigParameter::ShowPlayerStats, ConfigValue::Bool(false));
+    set_config_parameter(ConfigParameter::ShowRatingDeltas, ConfigValue::Bool(false));
+}
+
+fn parse_file_parallel(c: &mut Criterion) {
+
+    let runtime = tokio::runtime::Builder::new_multi_thread().build().unwrap();
+
+    let log_path = std::env::temp_dir().join("quake_log_parser_lib_bench_synthetic.log");
+    let mut log_file = std::fs::File::create(&log_path).unwrap();
+    let summary = SyntheticLog::new(SEED).with_matches(400).with_players(16).with_kills_per_match(200).write_to(&mut log_file).unwrap();
+    let log_path = log_path.to_string_lossy().to_string();
+    let log_size = std::fs::metadata(&log_path).unwrap().len();
+
+    let mut group = c.benchmark_group("synthetic_parse_file_parallel");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(log_size));
+
+    for workers in [1, 0] {
+        group.bench_with_input(BenchmarkId::new(format!("matches_{}/workers", summary.matches), workers), &workers, |b, workers| b.iter(|| {
+            runtime.block_on(factory().parse_file_parallel(log_path.clone(), *workers)).unwrap()
+        }));
+    }
+
+    group.finish();
+
+    let _ = std::fs::remove_file(&log_path);
+}
+
+criterion_group!(benches, event_detection, parse_log, parse_log_with_stats, parse_file_parallel);
+criterion_main!(benches);
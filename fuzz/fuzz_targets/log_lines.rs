@@ -0,0 +1,61 @@
+#![no_main]
+#![allow(clippy::needless_return)]
+
+//! Feeds arbitrary bytes through event detection and match aggregation as a whole log.
+//!
+//! Run from the repository root, where `STATIC_CONFIG.json` is found, seeding the corpus with
+//! the fixture logs: `mkdir -p fuzz/corpus/log_lines && cp sample_log.log tests/fixtures/synthetic.log fuzz/corpus/log_lines/`
+//! then `cargo +nightly fuzz run log_lines`.
+
+use std::io::Cursor;
+use std::sync::OnceLock;
+
+use libfuzzer_sys::fuzz_target;
+use tokio::runtime::Runtime;
+
+use quake_log_parser_lib::{
+    config::{
+        config::ConfigValue,
+        dynamic_config::{CONFIG, ConfigParameter}
+    },
+    implementation::log_event::is_known_log_line,
+    lib::factory
+};
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+fuzz_target!(|data: &[u8]| {
+
+    // The first byte picks the kills_rules flags, the rest is the log.
+    let Some((kills_rules, log)) = data.split_first() else {
+        return;
+    };
+
+    let parameters = [
+        ConfigParameter::BeingKilledDecreasesScore,
+        ConfigParameter::KillYourselfIncreasesScore,
+        ConfigParameter::ShowDeathCauses,
+        ConfigParameter::ShowPlayerStats
+    ];
+
+    for (bit, parameter) in parameters.into_iter().enumerate() {
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(parameter, ConfigValue::Bool(kills_rules & (1 << bit) != 0));
+        });
+    }
+
+    let log = String::from_utf8_lossy(log).into_owned();
+
+    for line in log.lines() {
+        let _known = is_known_log_line(line);
+    }
+
+    let runtime = RUNTIME.get_or_init(|| tokio::runtime::Builder::new_current_thread().build().unwrap());
+
+    let mut log_parser_service = factory();
+    log_parser_service.register_warning_callback(Box::new(|_payload| Box::pin(async { Ok(()) })));
+
+    let report = runtime.block_on(log_parser_service.parse_log(Box::new(Cursor::new(log.into_bytes())), None));
+
+    assert!(report.is_ok());
+});
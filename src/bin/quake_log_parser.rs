@@ -0,0 +1,329 @@
+#![allow(clippy::needless_return)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand};
+use serde_json::{Map, Value, json};
+
+use quake_log_parser_lib::{
+    errors::{ConfigError, LogParserError},
+    config::{
+        config::ConfigValue,
+        config_loader::ConfigLoader,
+        dynamic_config::{CONFIG, Config, ConfigParameter, PlayersOrder, ReportFormat},
+        static_config::validate_static_config
+    },
+    implementation::{
+        diagnostics::ParseMode,
+        leaderboard::{IdentityRules, Leaderboard},
+        log_event::is_known_log_line,
+        log_reader::{follow_log_file, open_log_file}
+    },
+    lib::factory
+};
+
+const STDIN_INPUT: &str = "-";
+
+#[derive(Parser)]
+#[command(name = "quake-log-parser", version, about = "Parses Quake 3 Arena server logs into match reports.")]
+struct Cli {
+    /// Config file, as config.json. TOML and YAML files need the `toml` and `yaml` features.
+    #[arg(long, global = true)]
+    config: Option<String>,
+
+    #[command(flatten)]
+    kills_rules: KillsRulesArgs,
+
+    #[command(subcommand)]
+    command: Command
+}
+
+/// Overrides of the `kills_rules` section of the config file.
+#[derive(Args)]
+struct KillsRulesArgs {
+    /// Being killed by <world> decreases the victim score.
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "true", value_name = "BOOL")]
+    being_killed_decreases_score: Option<bool>,
+
+    /// Killing yourself increases your score.
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "true", value_name = "BOOL")]
+    kill_yourself_increases_score: Option<bool>,
+
+    /// Adds kills by death cause to each match.
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "true", value_name = "BOOL")]
+    show_death_causes: Option<bool>,
+
+    /// Adds per player stats to each match.
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "true", value_name = "BOOL")]
+    show_player_stats: Option<bool>
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Parses a log file, or stdin, into a report.
+    Parse {
+        /// Log file path, `-` or nothing to read stdin.
+        input: Option<String>,
+
+        /// json, pretty_json or ndjson.
+        #[arg(long, value_parser = ReportFormat::from_str)]
+        format: Option<ReportFormat>,
+
+        /// name or score.
+        #[arg(long, value_parser = PlayersOrder::from_str)]
+        players_order: Option<PlayersOrder>,
+
+        /// Wraps matches into a versioned report envelope.
+        #[arg(long)]
+        envelope: bool,
+
+        /// Splits a log file at match boundaries and parses its matches on this many threads, 0 for all cores.
+        #[arg(long, value_name = "THREADS")]
+        parallel: Option<usize>,
+
+        /// strict, lenient or collect.
+        #[arg(long, value_parser = ParseMode::from_str, default_value = "lenient")]
+        parse_mode: ParseMode
+    },
+    /// Follows a growing log file, printing each finished match as a JSON line.
+    Tail {
+        log_file: String,
+
+        /// Parses the lines already in the file before following it.
+        #[arg(long)]
+        from_start: bool,
+
+        #[arg(long, default_value_t = 500, value_name = "MILLISECONDS")]
+        poll_interval: u64
+    },
+    /// Aggregates lifetime player stats across log files, directories or glob patterns.
+    Stats {
+        #[arg(required = true)]
+        inputs: Vec<String>,
+
+        /// Parses the log files of each input concurrently.
+        #[arg(long)]
+        concurrent: bool,
+
+        /// Treats player names differing only by case as the same player.
+        #[arg(long)]
+        case_insensitive: bool,
+
+        /// Ignores Quake color codes (`^1`, `^7`, ...) in player names.
+        #[arg(long)]
+        strip_color_codes: bool
+    },
+    /// Reports log lines the parser does not understand. Exits with failure when any is found.
+    Validate {
+        /// Log file path, `-` or nothing to read stdin.
+        input: Option<String>
+    }
+}
+
+fn print_output(output: &str) -> Result<(), LogParserError> {
+    let mut stdout = std::io::stdout().lock();
+    return writeln!(stdout, "{}", output).map_err(|_e| LogParserError::WriteError);
+}
+
+fn set_config_parameter(parameter: ConfigParameter, value: ConfigValue) {
+    CONFIG.with(|config| {
+        config.borrow_mut().set_parameter(parameter, value);
+    });
+}
+
+fn kills_rules_overrides(kills_rules: &KillsRulesArgs) -> Value {
+
+    let overrides = [
+        ("being_killed_decreases_score", kills_rules.being_killed_decreases_score),
+        ("kill_yourself_increases_score", kills_rules.kill_yourself_increases_score),
+        ("show_death_causes", kills_rules.show_death_causes),
+        ("show_player_stats", kills_rules.show_player_stats)
+    ];
+
+    let kills_rules: Map<String, Value> = overrides.into_iter()
+        .filter_map(|(key, value)| value.map(|value| (String::from(key), Value::Bool(value))))
+        .collect();
+
+    return json!({ "kills_rules": kills_rules });
+}
+
+/// Built-in defaults, then `--config`, then `QLP_*` environment variables, then flags.
+fn install_config(cli: &Cli) -> Result<(), ConfigError> {
+
+    let mut config_loader = ConfigLoader::new().with_env();
+
+    if let Some(config) = &cli.config {
+        config_loader = config_loader.with_file(config);
+    }
+
+    config_loader.with_overrides(kills_rules_overrides(&cli.kills_rules)).load()?.install();
+
+    return Ok(());
+}
+
+fn open_input(input: &Option<String>) -> Result<(Box<dyn BufRead>, Option<String>), LogParserError> {
+    match input.as_deref() {
+        None | Some(STDIN_INPUT) => Ok((Box::new(BufReader::new(std::io::stdin())), None)),
+        Some(path) => Ok((open_log_file(path)?, Some(String::from(path))))
+    }
+}
+
+async fn parse(input: Option<String>, format: Option<ReportFormat>, players_order: Option<PlayersOrder>, envelope: bool, parallel: Option<usize>, parse_mode: ParseMode) -> Result<(), LogParserError> {
+
+    if let Some(format) = format {
+        set_config_parameter(ConfigParameter::ReportFormat, ConfigValue::Str(format.to_string()));
+    }
+
+    if let Some(players_order) = players_order {
+        set_config_parameter(ConfigParameter::PlayersOrder, ConfigValue::Str(players_order.to_string()));
+    }
+
+    if envelope {
+        set_config_parameter(ConfigParameter::ReportEnvelope, ConfigValue::Bool(true));
+    }
+
+    set_config_parameter(ConfigParameter::StreamMatches, ConfigValue::Bool(false));
+
+    let mut log_parser_service = factory();
+    log_parser_service.set_parse_mode(parse_mode);
+
+    let report = match (input, parallel) {
+        (Some(path), Some(workers)) if path != STDIN_INPUT => log_parser_service.parse_file_parallel(path, workers).await?,
+        (input, _parallel) => {
+            let (reader, source) = open_input(&input)?;
+            log_parser_service.parse_log(reader, source).await?
+        }
+    };
+
+    print_output(report.trim_end())?;
+
+    return Ok(());
+}
+
+async fn tail(log_file: String, from_start: bool, poll_interval: u64) -> Result<(), LogParserError> {
+
+    set_config_parameter(ConfigParameter::StreamMatches, ConfigValue::Bool(true));
+
+    let mut config_snapshot: Option<Config> = None;
+
+    CONFIG.with(|config| {
+        config_snapshot = Some(config.borrow().clone());
+    });
+
+    // The followed log sleeps between polls, so it is read on a blocking thread, not on the runtime one.
+    let task = tokio::task::spawn_blocking(move || {
+
+        if let Some(config_snapshot) = config_snapshot {
+            config_snapshot.install();
+        }
+
+        let reader = follow_log_file(&log_file, from_start, Duration::from_millis(poll_interval))?;
+
+        let mut log_parser_service = factory();
+        log_parser_service.register_match_writer(Box::new(std::io::stdout()));
+
+        return tokio::runtime::Handle::current().block_on(log_parser_service.parse_log(reader, Some(log_file)));
+    });
+
+    task.await.map_err(|_e| LogParserError::UnexpectedError)??;
+
+    return Ok(());
+}
+
+async fn stats(inputs: Vec<String>, concurrent: bool, identity_rules: IdentityRules) -> Result<(), LogParserError> {
+
+    set_config_parameter(ConfigParameter::ShowPlayerStats, ConfigValue::Bool(true));
+    set_config_parameter(ConfigParameter::ReportFormat, ConfigValue::Str(ReportFormat::Json.to_string()));
+    set_config_parameter(ConfigParameter::StreamMatches, ConfigValue::Bool(false));
+
+    let mut leaderboard = Leaderboard::new(identity_rules);
+
+    for input in inputs {
+        let report = factory().parse_files(input, concurrent).await?;
+        let report: Value = serde_json::from_str(&report).map_err(|_e| LogParserError::SerializationError)?;
+        leaderboard.fold_report(&report)?;
+    }
+
+    let ranking = serde_json::to_string_pretty(&leaderboard.to_json()?).map_err(|_e| LogParserError::StringfyError)?;
+    print_output(&ranking)?;
+
+    return Ok(());
+}
+
+fn validate(input: Option<String>) -> Result<bool, LogParserError> {
+
+    let (mut reader, _source) = open_input(&input)?;
+
+    let mut lines_checked: usize = 0;
+    let mut unknown_lines: Vec<Value> = Vec::new();
+    let mut line_bytes: Vec<u8> = Vec::new();
+
+    loop {
+
+        line_bytes.clear();
+
+        if reader.read_until(b'\n', &mut line_bytes).map_err(|_e| LogParserError::ReadFileError)? == 0 {
+            break;
+        }
+
+        let line_len = line_bytes.len() - line_bytes.iter().rev().take_while(|byte| **byte == b'\n' || **byte == b'\r').count();
+        line_bytes.truncate(line_len);
+
+        lines_checked += 1;
+
+        // A line that is not valid UTF-8 is skipped by the parser, so it is unknown too.
+        if !std::str::from_utf8(&line_bytes).is_ok_and(is_known_log_line) {
+            unknown_lines.push(json!({ "line": lines_checked, "text": String::from_utf8_lossy(&line_bytes) }));
+        }
+    }
+
+    let valid = unknown_lines.is_empty();
+
+    let validation = json!({
+        "lines_checked": lines_checked,
+        "unknown_lines_count": unknown_lines.len(),
+        "unknown_lines": unknown_lines
+    });
+
+    print_output(&serde_json::to_string_pretty(&validation).map_err(|_e| LogParserError::StringfyError)?)?;
+
+    return Ok(valid);
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+
+    let cli = Cli::parse();
+
+    if let Err(err) = install_config(&cli) {
+        eprintln!("{}", err);
+        return ExitCode::FAILURE;
+    }
+
+    if let Err(err) = validate_static_config() {
+        eprintln!("{}", err);
+        return ExitCode::FAILURE;
+    }
+
+    let result = match cli.command {
+        Command::Parse { input, format, players_order, envelope, parallel, parse_mode } => parse(input, format, players_order, envelope, parallel, parse_mode).await.map(|_| true),
+        Command::Tail { log_file, from_start, poll_interval } => tail(log_file, from_start, poll_interval).await.map(|_| true),
+        Command::Stats { inputs, concurrent, case_insensitive, strip_color_codes } => {
+            let identity_rules = IdentityRules { case_insensitive, strip_color_codes, ..Default::default() };
+            stats(inputs, concurrent, identity_rules).await.map(|_| true)
+        },
+        Command::Validate { input } => validate(input)
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -5,6 +5,7 @@ pub enum ConfigValue {
 }
 
 impl ConfigValue {
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         match self {
             ConfigValue::Str(value) => value.clone(),
@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use serde_json::{Map, Value};
+
+use crate::errors::ConfigError;
+use crate::config::dynamic_config::{Config, merge_json};
+
+pub const ENV_PREFIX: &str = "QLP_";
+const ENV_KEY_SEPARATOR: &str = "__";
+const DEFAULTS_SOURCE: &str = "defaults";
+const OVERRIDES_SOURCE: &str = "overrides";
+const LOG_FILE_PATH_KEY: &str = "log_file_path";
+
+/// Builds a `Config` from layers, each one overriding the previous ones:
+/// built-in defaults, then a config file, then `QLP_*` environment variables, then overrides.
+///
+/// Config files are read as JSON, TOML or YAML according to their extension.
+/// Environment variables are only read after `with_env`. They name a key path with `__` between
+/// sections, e.g. `QLP_KILLS_RULES__SHOW_DEATH_CAUSES=true` or `QLP_OUTPUT_RULES__FORMAT=ndjson`.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLoader {
+    file_path: Option<String>,
+    env_prefix: Option<String>,
+    env_vars: Option<BTreeMap<String, String>>,
+    overrides: Vec<Value>
+}
+
+impl ConfigLoader {
+    pub fn new() -> Self {
+        return ConfigLoader::default();
+    }
+
+    pub fn with_file(mut self, path: &str) -> Self {
+        self.file_path = Some(String::from(path));
+        return self;
+    }
+
+    pub fn with_env(self) -> Self {
+        return self.with_env_prefix(ENV_PREFIX);
+    }
+
+    pub fn with_env_prefix(mut self, prefix: &str) -> Self {
+        self.env_prefix = Some(String::from(prefix));
+        return self;
+    }
+
+    /// Reads the environment layer from `env_vars` instead of the process environment.
+    pub fn with_env_vars(mut self, env_vars: BTreeMap<String, String>) -> Self {
+        self.env_vars = Some(env_vars);
+        return self;
+    }
+
+    /// Partial config json, e.g. `{"output_rules": {"format": "ndjson"}}`. Applied in call order.
+    pub fn with_overrides(mut self, overrides: Value) -> Self {
+        self.overrides.push(overrides);
+        return self;
+    }
+
+    pub fn load(&self) -> Result<Config, ConfigError> {
+
+        let mut defaults = serde_json::to_value(Config::default()).map_err(|err| ConfigError::SyntaxError {
+            source: String::from(DEFAULTS_SOURCE),
+            message: err.to_string()
+        })?;
+
+        if let Value::Object(defaults) = &mut defaults {
+            defaults.insert(String::from(LOG_FILE_PATH_KEY), Value::Null);
+        }
+
+        let mut layers: Vec<(String, Value)> = Vec::new();
+
+        if let Some(file_path) = &self.file_path {
+            layers.push((file_path.clone(), read_config_file(file_path)?));
+        }
+
+        if let Some(env_prefix) = &self.env_prefix {
+            match &self.env_vars {
+                Some(env_vars) => layers.extend(env_layers(env_prefix, env_vars.clone().into_iter())),
+                None => layers.extend(env_layers(env_prefix, std::env::vars()))
+            }
+        }
+
+        for overrides in self.overrides.iter() {
+            layers.push((String::from(OVERRIDES_SOURCE), overrides.clone()));
+        }
+
+        let mut config_json = defaults.clone();
+
+        for (source, layer) in layers.iter() {
+            validate_layer(&defaults, source, layer)?;
+            merge_json(&mut config_json, layer);
+        }
+
+        return serde_json::from_value(config_json).map_err(|err| ConfigError::SyntaxError {
+            source: layers.iter().map(|(source, _layer)| source.clone()).collect::<Vec<String>>().join(", "),
+            message: err.to_string()
+        });
+    }
+}
+
+fn read_config_file(path: &str) -> Result<Value, ConfigError> {
+
+    let file_content = std::fs::read_to_string(path).map_err(|_e| ConfigError::ReadError { path: String::from(path) })?;
+
+    match Path::new(path).extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::from_str(&file_content).map_err(|err| ConfigError::SyntaxError { source: String::from(path), message: err.to_string() }),
+        Some("toml") => toml_config(path, &file_content),
+        Some("yaml") | Some("yml") => yaml_config(path, &file_content),
+        _ => Err(ConfigError::UnsupportedFormat { path: String::from(path) })
+    }
+}
+
+#[cfg(feature = "toml")]
+fn toml_config(path: &str, file_content: &str) -> Result<Value, ConfigError> {
+    return toml::from_str(file_content).map_err(|err| ConfigError::SyntaxError { source: String::from(path), message: err.to_string() });
+}
+
+#[cfg(not(feature = "toml"))]
+fn toml_config(path: &str, _file_content: &str) -> Result<Value, ConfigError> {
+    return Err(ConfigError::UnsupportedFormat { path: String::from(path) });
+}
+
+#[cfg(feature = "yaml")]
+fn yaml_config(path: &str, file_content: &str) -> Result<Value, ConfigError> {
+    return serde_yaml_ng::from_str(file_content).map_err(|err| ConfigError::SyntaxError { source: String::from(path), message: err.to_string() });
+}
+
+#[cfg(not(feature = "yaml"))]
+fn yaml_config(path: &str, _file_content: &str) -> Result<Value, ConfigError> {
+    return Err(ConfigError::UnsupportedFormat { path: String::from(path) });
+}
+
+/// One layer per matching variable, in name order, so errors can name the variable.
+fn env_layers(env_prefix: &str, vars: impl Iterator<Item = (String, String)>) -> Vec<(String, Value)> {
+
+    let vars: BTreeMap<String, String> = vars.filter(|(name, _value)| name.starts_with(env_prefix)).collect();
+
+    return vars.into_iter().map(|(name, value)| {
+
+        let key_path: Vec<String> = name[env_prefix.len()..].split(ENV_KEY_SEPARATOR).map(|key| key.to_lowercase()).collect();
+        let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+
+        let layer = nested_value(&key_path, value);
+
+        (name, layer)
+    }).collect();
+}
+
+/// `["kills_rules", "show_death_causes"]` and `true` become `{"kills_rules": {"show_death_causes": true}}`.
+fn nested_value(key_path: &[String], value: Value) -> Value {
+    return key_path.iter().rev().fold(value, |value, key| {
+        let mut section = Map::new();
+        section.insert(key.clone(), value);
+        Value::Object(section)
+    });
+}
+
+fn validate_layer(defaults: &Value, source: &str, layer: &Value) -> Result<(), ConfigError> {
+
+    if !layer.is_object() {
+        return Err(ConfigError::SyntaxError { source: String::from(source), message: String::from("expected a table of config sections") });
+    }
+
+    return validate_keys(defaults, defaults, source, layer, &mut Vec::new());
+}
+
+fn validate_keys(defaults: &Value, section_defaults: &Value, source: &str, section: &Value, key_path: &mut Vec<String>) -> Result<(), ConfigError> {
+
+    let (Some(section_defaults), Some(section)) = (section_defaults.as_object(), section.as_object()) else {
+        return validate_value(defaults, source, section, key_path);
+    };
+
+    for (key, value) in section.iter() {
+
+        key_path.push(key.clone());
+
+        let Some(key_defaults) = section_defaults.get(key) else {
+            return Err(ConfigError::UnknownKey { source: String::from(source), key: key_path.join(".") });
+        };
+
+        if key_defaults.is_object() {
+            validate_keys(defaults, key_defaults, source, value, key_path)?;
+        } else {
+            validate_value(defaults, source, value, key_path)?;
+        }
+
+        key_path.pop();
+    }
+
+    return Ok(());
+}
+
+/// Deserializes the defaults with a single key replaced, so a bad value is reported by its own key.
+fn validate_value(defaults: &Value, source: &str, value: &Value, key_path: &[String]) -> Result<(), ConfigError> {
+
+    let candidate_layer = nested_value(key_path, value.clone());
+
+    let mut candidate = defaults.clone();
+    merge_json(&mut candidate, &candidate_layer);
+
+    return serde_json::from_value::<Config>(candidate).map(|_config| ()).map_err(|err| ConfigError::InvalidValue {
+        source: String::from(source),
+        key: key_path.join("."),
+        message: err.to_string()
+    });
+}
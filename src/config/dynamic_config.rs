@@ -1,51 +1,73 @@
+use std::fmt;
+use std::str::FromStr;
 use std::cell::RefCell;
-use std::fs::File;
-use std::io::Read;
-use serde::Deserialize;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
 
-use crate::config::config::ConfigValue;
+use crate::errors::{ConfigError, LogParserError};
+use crate::config::{
+    config::ConfigValue,
+    config_loader::ConfigLoader
+};
 
 pub enum ConfigParameter {
     LogFilePath,
     BeingKilledDecreasesScore,
     KillYourselfIncreasesScore,
-    ShowDeathCauses
+    ShowDeathCauses,
+    ShowPlayerStats,
+    PlayersOrder,
+    ReportEnvelope,
+    ReportFormat,
+    StreamMatches,
+    ShowRatingDeltas,
+    RatingsFilePath,
+    OrphanEvents
 }
 
-thread_local!(pub static CONFIG_FILE_PATH: RefCell<Option<String>> = RefCell::new(None) );
+thread_local!(pub static CONFIG_FILE_PATH: RefCell<Option<String>> = const { RefCell::new(None) } );
+
+thread_local!(static CONFIG_LOAD_ERROR: RefCell<Option<ConfigError>> = const { RefCell::new(None) } );
 
 thread_local!(pub static CONFIG: RefCell<Config> = {
 
-    let mut path:String =  String::from(""); 
+    let config_file_path = CONFIG_FILE_PATH.with(|config_file_path_handler| config_file_path_handler.borrow().clone());
 
-    CONFIG_FILE_PATH.with(|config_file_path_handler| {
-        
-        if let Some(config_file_path) = config_file_path_handler.borrow().as_ref() {
-            path = config_file_path.clone();
-        }
-    });
+    // No environment layer: it is up to the embedding app, as the CLI, to opt in with `ConfigLoader::with_env`.
+    let mut config_loader = ConfigLoader::new();
 
-    if path == "" {
-        return RefCell::new(Config {
-            kills_rules: KillsRules {
-                being_killed_decreases_score: false,
-                kill_yourself_increases_score: false,
-                show_death_causes: false
-            },
-            log_file_path: None
-        });
+    if let Some(config_file_path) = config_file_path {
+        config_loader = config_loader.with_file(&config_file_path);
     }
 
-    let mut file = File::open(path).expect("Unable to open config file");
-    let mut file_content = String::new();
-    file.read_to_string(&mut file_content).expect("Unable to read config file");
-    return RefCell::new(serde_json::from_str(&file_content).expect("Unable to parse config Json file"));
+    // A bad config falls back to default rules, `validate_config` reports it before parsing.
+    let config = config_loader.load().unwrap_or_else(|err| {
+        CONFIG_LOAD_ERROR.with(|config_load_error| *config_load_error.borrow_mut() = Some(err));
+        return Config::default();
+    });
+
+    return RefCell::new(config);
 });
 
+/// Loads the config of the calling thread, if not loaded yet, and returns the error of a config
+/// file that could not be loaded. Log parsing calls it first,
+/// so a bad config is reported instead of panicking.
+pub fn validate_config() -> Result<(), ConfigError> {
+    CONFIG.with(|_config| ());
+    return CONFIG_LOAD_ERROR.with(|config_load_error| config_load_error.borrow().clone().map_or(Ok(()), Err));
+}
+
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
     kills_rules: KillsRules,
+    #[serde(default)]
+    output_rules: OutputRules,
+    #[serde(default)]
+    rating_rules: RatingRules,
+    #[serde(default)]
+    parse_rules: ParseRules,
+    #[serde(skip_serializing)]
     log_file_path: Option<String>
 }
 
@@ -55,6 +77,14 @@ impl Config {
             ConfigParameter::BeingKilledDecreasesScore => ConfigValue::Bool(self.kills_rules.being_killed_decreases_score),
             ConfigParameter::KillYourselfIncreasesScore => ConfigValue::Bool(self.kills_rules.kill_yourself_increases_score),
             ConfigParameter::ShowDeathCauses => ConfigValue::Bool(self.kills_rules.show_death_causes),
+            ConfigParameter::ShowPlayerStats => ConfigValue::Bool(self.kills_rules.show_player_stats),
+            ConfigParameter::PlayersOrder => ConfigValue::Str(self.output_rules.players_order.to_string()),
+            ConfigParameter::ReportEnvelope => ConfigValue::Bool(self.output_rules.report_envelope),
+            ConfigParameter::ReportFormat => ConfigValue::Str(self.output_rules.format.to_string()),
+            ConfigParameter::StreamMatches => ConfigValue::Bool(self.output_rules.stream_matches),
+            ConfigParameter::ShowRatingDeltas => ConfigValue::Bool(self.rating_rules.show_rating_deltas),
+            ConfigParameter::RatingsFilePath => ConfigValue::OptStr(self.rating_rules.ratings_file_path.clone()),
+            ConfigParameter::OrphanEvents => ConfigValue::Str(self.parse_rules.orphan_events.to_string()),
             ConfigParameter::LogFilePath => ConfigValue::OptStr(self.log_file_path.clone())
         }
     }
@@ -64,14 +94,174 @@ impl Config {
             ConfigParameter::BeingKilledDecreasesScore => { self.kills_rules.being_killed_decreases_score = value.to_boolean() },
             ConfigParameter::KillYourselfIncreasesScore => { self.kills_rules.kill_yourself_increases_score = value.to_boolean() },
             ConfigParameter::ShowDeathCauses => { self.kills_rules.show_death_causes = value.to_boolean() },
+            ConfigParameter::ShowPlayerStats => { self.kills_rules.show_player_stats = value.to_boolean() },
+            ConfigParameter::PlayersOrder => { self.output_rules.players_order = PlayersOrder::from_str(value.to_string().as_str()).unwrap_or_default() },
+            ConfigParameter::ReportEnvelope => { self.output_rules.report_envelope = value.to_boolean() },
+            ConfigParameter::ReportFormat => { self.output_rules.format = ReportFormat::from_str(value.to_string().as_str()).unwrap_or_default() },
+            ConfigParameter::StreamMatches => { self.output_rules.stream_matches = value.to_boolean() },
+            ConfigParameter::ShowRatingDeltas => { self.rating_rules.show_rating_deltas = value.to_boolean() },
+            ConfigParameter::RatingsFilePath => { self.rating_rules.ratings_file_path = value.to_optional_string() },
+            ConfigParameter::OrphanEvents => { self.parse_rules.orphan_events = OrphanEvents::from_str(value.to_string().as_str()).unwrap_or_default() },
             ConfigParameter::LogFilePath => { self.log_file_path = value.to_optional_string() }
         }
     }
+
+    /// Replaces the config of the calling thread, without loading `CONFIG_FILE_PATH`.
+    pub fn install(self) {
+        CONFIG.set(self);
+        CONFIG_LOAD_ERROR.set(None);
+    }
+
+    /// Returns a copy of this config with the given partial config json merged on top of it,
+    /// e.g. `{"kills_rules": {"show_death_causes": true}}`.
+    pub fn with_overrides(&self, overrides: &Value) -> Result<Config, LogParserError> {
+
+        let mut config_json = serde_json::to_value(self).map_err(|_e| LogParserError::SerializationError)?;
+
+        merge_json(&mut config_json, overrides);
+
+        let mut config: Config = serde_json::from_value(config_json).map_err(|_e| LogParserError::SerializationError)?;
+        config.log_file_path = self.log_file_path.clone();
+
+        return Ok(config);
+    }
 }
 
-#[derive(Debug, Deserialize)]
+pub(crate) fn merge_json(base: &mut Value, overrides: &Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides.iter() {
+                merge_json(base.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        },
+        (base, overrides) => { *base = overrides.clone(); }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct KillsRules {
     being_killed_decreases_score: bool,
     kill_yourself_increases_score: bool,
-    show_death_causes: bool
+    show_death_causes: bool,
+    #[serde(default)]
+    show_player_stats: bool
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct OutputRules {
+    #[serde(default)]
+    players_order: PlayersOrder,
+    #[serde(default)]
+    report_envelope: bool,
+    #[serde(default)]
+    format: ReportFormat,
+    #[serde(default)]
+    stream_matches: bool
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct RatingRules {
+    #[serde(default)]
+    show_rating_deltas: bool,
+    #[serde(default)]
+    ratings_file_path: Option<String>
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct ParseRules {
+    #[serde(default)]
+    orphan_events: OrphanEvents
+}
+
+/// What becomes of events logged before the first `InitGame`, as in a log rotated mid-match.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanEvents {
+    /// Kept in a match of their own, flagged as partial.
+    #[default]
+    PartialMatch,
+    /// Dropped, each with an `orphaned_event` diagnostic.
+    Discard
+}
+
+impl fmt::Display for OrphanEvents {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrphanEvents::PartialMatch => write!(f, "partial_match"),
+            OrphanEvents::Discard => write!(f, "discard")
+        }
+    }
+}
+
+impl FromStr for OrphanEvents {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "partial_match" => Ok(OrphanEvents::PartialMatch),
+            "discard" => Ok(OrphanEvents::Discard),
+            _ => Err(format!("Invalid Orphan Events: {}", s))
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayersOrder {
+    #[default]
+    Name,
+    Score
+}
+
+impl fmt::Display for PlayersOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayersOrder::Name => write!(f, "name"),
+            PlayersOrder::Score => write!(f, "score")
+        }
+    }
+}
+
+impl FromStr for PlayersOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "name" => Ok(PlayersOrder::Name),
+            "score" => Ok(PlayersOrder::Score),
+            _ => Err(format!("Invalid Players Order: {}", s))
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    PrettyJson,
+    Ndjson
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReportFormat::Json => write!(f, "json"),
+            ReportFormat::PrettyJson => write!(f, "pretty_json"),
+            ReportFormat::Ndjson => write!(f, "ndjson")
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "json" => Ok(ReportFormat::Json),
+            "pretty_json" => Ok(ReportFormat::PrettyJson),
+            "ndjson" => Ok(ReportFormat::Ndjson),
+            _ => Err(format!("Invalid Report Format: {}", s))
+        }
+    }
 }
\ No newline at end of file
@@ -1,3 +1,5 @@
+#[allow(clippy::module_inception)]
 pub mod config;
 pub mod static_config;
-pub mod dynamic_config;
\ No newline at end of file
+pub mod dynamic_config;
+pub mod config_loader;
\ No newline at end of file
@@ -1,10 +1,19 @@
-use std::fs::File;
-use std::io::Read;
+use std::fs;
+use std::io::ErrorKind;
 use serde::Deserialize;
 use lazy_static::lazy_static;
+use once_cell::sync::Lazy;
+use regex::Regex;
 
 use crate::config::config::ConfigValue;
+use crate::errors::{InvalidPattern, StaticConfigError};
 
+const REGEX_PATTERNS_SECTION: &str = "regex_pattern_engine";
+const STATIC_CONFIG_FILE_PATH: &str = "STATIC_CONFIG.json";
+/// Static config shipped with the crate, used when the working directory has no `STATIC_CONFIG.json`.
+const EMBEDDED_STATIC_CONFIG: &str = include_str!("../../STATIC_CONFIG.json");
+
+#[derive(Clone, Copy)]
 pub(crate) enum StaticConfigParameter {
     InitGameEventRegex,
     ClientConnectEventRegex,
@@ -17,29 +26,48 @@ pub(crate) enum StaticConfigParameter {
     ExitEventRegex,
     KillEventLineParserRegex,
     UserInfoLineParserRegex,
+    UserTeamLineParserRegex,
+    GameTypeLineParserRegex,
+    ClientIdLineParserRegex,
+    ItemEventLineParserRegex,
     WorldLogPattern,
     OutputMatchKey,
     TotalKillsKey,
     PlayersKey,
     KillsKey,
     KillByMeansKey,
-    InvalidKillMeanTokenErrMsg,
-    LogFilePathNotFoundErrMsg
+    ProvenanceKey,
+    PlayerStatsKey,
+    RatingDeltasKey,
+    PartialKey,
+    InvalidKillMeanTokenErrMsg
 }
 
 lazy_static! {
 
-    pub(crate) static ref STATIC_CONFIG: StaticConfig = {
+    static ref STATIC_CONFIG_LOAD: Result<StaticConfig, StaticConfigError> = load_static_config();
 
-        let mut file = File::open("STATIC_CONFIG.json").expect("Unable to open static config file");
-        let mut file_content = String::new();
-        file.read_to_string(&mut file_content).expect("Unable to read static config file");
-        return serde_json::from_str(&file_content).expect("Unable to parse static config Json file");
+    /// A `STATIC_CONFIG.json` that can not be read or parsed is reported by `validate_static_config`,
+    /// meanwhile the embedded static config is used.
+    pub(crate) static ref STATIC_CONFIG: StaticConfig = match STATIC_CONFIG_LOAD.as_ref() {
+        Ok(static_config) => static_config.clone(),
+        Err(_err) => EMBEDDED_DEFAULTS.clone()
+    };
+}
 
+fn load_static_config() -> Result<StaticConfig, StaticConfigError> {
+
+    let file_content = match fs::read_to_string(STATIC_CONFIG_FILE_PATH) {
+        Ok(file_content) => file_content,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::from(EMBEDDED_STATIC_CONFIG),
+        Err(err) => return Err(StaticConfigError::LoadError { path: String::from(STATIC_CONFIG_FILE_PATH), message: err.to_string() })
     };
+
+    return serde_json::from_str(&file_content)
+        .map_err(|err| StaticConfigError::LoadError { path: String::from(STATIC_CONFIG_FILE_PATH), message: err.to_string() });
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub(crate) struct StaticConfig {
     regex_pattern_engine: RegexPatterns,
     log_patterns: LogPatterns,
@@ -61,28 +89,141 @@ impl StaticConfig {
             StaticConfigParameter::ExitEventRegex => ConfigValue::Str(self.regex_pattern_engine.exit_event.clone()),
             StaticConfigParameter::KillEventLineParserRegex => ConfigValue::Str(self.regex_pattern_engine.kill_event_line_parser.clone()),
             StaticConfigParameter::UserInfoLineParserRegex => ConfigValue::Str(self.regex_pattern_engine.user_info_line_parser.clone()),
+            StaticConfigParameter::UserTeamLineParserRegex => ConfigValue::Str(self.regex_pattern_engine.user_team_line_parser.clone()),
+            StaticConfigParameter::GameTypeLineParserRegex => ConfigValue::Str(self.regex_pattern_engine.game_type_line_parser.clone()),
+            StaticConfigParameter::ClientIdLineParserRegex => ConfigValue::Str(self.regex_pattern_engine.client_id_line_parser.clone()),
+            StaticConfigParameter::ItemEventLineParserRegex => ConfigValue::Str(self.regex_pattern_engine.item_event_line_parser.clone()),
             StaticConfigParameter::WorldLogPattern => ConfigValue::Str(self.log_patterns.world.clone()),
             StaticConfigParameter::KillsKey => ConfigValue::Str(self.log_patterns.kills_key.clone()),
             StaticConfigParameter::PlayersKey => ConfigValue::Str(self.log_patterns.players_key.clone()),
             StaticConfigParameter::TotalKillsKey => ConfigValue::Str(self.log_patterns.total_kills_key.clone()),
             StaticConfigParameter::KillByMeansKey => ConfigValue::Str(self.log_patterns.kill_by_means_key.clone()),
+            StaticConfigParameter::ProvenanceKey => ConfigValue::Str(self.log_patterns.provenance_key.clone()),
+            StaticConfigParameter::PlayerStatsKey => ConfigValue::Str(self.log_patterns.player_stats_key.clone()),
+            StaticConfigParameter::RatingDeltasKey => ConfigValue::Str(self.log_patterns.rating_deltas_key.clone()),
+            StaticConfigParameter::PartialKey => ConfigValue::Str(self.log_patterns.partial_key.clone()),
             StaticConfigParameter::OutputMatchKey => ConfigValue::Str(self.output_format.match_key.clone()),
-            StaticConfigParameter::InvalidKillMeanTokenErrMsg => ConfigValue::Str(self.error_messages.invalid_kill_mean_token.clone()),
-            StaticConfigParameter::LogFilePathNotFoundErrMsg => ConfigValue::Str(self.error_messages.log_file_path_not_found.clone())
+            StaticConfigParameter::InvalidKillMeanTokenErrMsg => ConfigValue::Str(self.error_messages.invalid_kill_mean_token.clone())
         }
     }
+
+    /// Each regex with the number of capture groups the parser reads from it, if any.
+    fn regex_patterns(&self) -> Vec<(&'static str, &String, Option<usize>)> {
+        let patterns = &self.regex_pattern_engine;
+
+        return vec![
+            ("init_game_event", &patterns.init_game_event, None),
+            ("client_connect_event", &patterns.client_connect_event, None),
+            ("client_info_change_event", &patterns.client_info_change_event, None),
+            ("client_begin_event", &patterns.client_begin_event, None),
+            ("client_disconnect_event", &patterns.client_disconnect_event, None),
+            ("item_event", &patterns.item_event, None),
+            ("kill_event", &patterns.kill_event, None),
+            ("shutdown_event", &patterns.shutdown_event, None),
+            ("exit_event", &patterns.exit_event, None),
+            ("kill_event_line_parser", &patterns.kill_event_line_parser, Some(5)),
+            ("user_info_line_parser", &patterns.user_info_line_parser, Some(1)),
+            ("user_team_line_parser", &patterns.user_team_line_parser, Some(1)),
+            ("game_type_line_parser", &patterns.game_type_line_parser, Some(1)),
+            ("client_id_line_parser", &patterns.client_id_line_parser, Some(1)),
+            ("item_event_line_parser", &patterns.item_event_line_parser, Some(4))
+        ];
+    }
+
+    pub(crate) fn validate_regex_patterns(&self) -> Result<(), StaticConfigError> {
+
+        let mut invalid_patterns: Vec<InvalidPattern> = Vec::new();
+
+        for (key, pattern, capture_groups) in self.regex_patterns() {
+
+            let reason = match (Regex::new(pattern), capture_groups) {
+                (Err(err), _) => Some(err.to_string()),
+                (Ok(regex), Some(capture_groups)) if regex.captures_len() - 1 != capture_groups => {
+                    Some(format!("expected {} capture groups, found {}", capture_groups, regex.captures_len() - 1))
+                },
+                _ => None
+            };
+
+            if let Some(reason) = reason {
+                invalid_patterns.push(InvalidPattern {
+                    key: format!("{}.{}", REGEX_PATTERNS_SECTION, key),
+                    pattern: pattern.clone(),
+                    reason
+                });
+            }
+        }
+
+        if invalid_patterns.is_empty() {
+            return Ok(());
+        }
+
+        return Err(StaticConfigError::InvalidPatterns(invalid_patterns));
+    }
+}
+
+static STATIC_CONFIG_VALIDATION: Lazy<Result<(), StaticConfigError>> = Lazy::new(|| {
+    return STATIC_CONFIG_LOAD.as_ref().map_err(|err| err.clone()).and_then(|static_config| static_config.validate_regex_patterns());
+});
+
+/// Loads `STATIC_CONFIG.json`, compiles every regex and checks its capture groups, once per process.
+/// Log parsing calls it before the first line, so a bad static config is reported instead of panicking.
+pub fn validate_static_config() -> Result<(), StaticConfigError> {
+    return STATIC_CONFIG_VALIDATION.clone();
+}
+
+/// The embedded static config, filling in the keys a `STATIC_CONFIG.json` written for an older release lacks.
+static EMBEDDED_DEFAULTS: Lazy<StaticConfig> = Lazy::new(|| serde_json::from_str(EMBEDDED_STATIC_CONFIG).unwrap_or_default());
+
+fn embedded_provenance_key() -> String {
+    return EMBEDDED_DEFAULTS.log_patterns.provenance_key.clone();
+}
+
+fn embedded_player_stats_key() -> String {
+    return EMBEDDED_DEFAULTS.log_patterns.player_stats_key.clone();
+}
+
+fn embedded_rating_deltas_key() -> String {
+    return EMBEDDED_DEFAULTS.log_patterns.rating_deltas_key.clone();
+}
+
+fn embedded_partial_key() -> String {
+    return EMBEDDED_DEFAULTS.log_patterns.partial_key.clone();
+}
+
+fn embedded_user_team_line_parser() -> String {
+    return EMBEDDED_DEFAULTS.regex_pattern_engine.user_team_line_parser.clone();
+}
+
+fn embedded_game_type_line_parser() -> String {
+    return EMBEDDED_DEFAULTS.regex_pattern_engine.game_type_line_parser.clone();
+}
+
+fn embedded_client_id_line_parser() -> String {
+    return EMBEDDED_DEFAULTS.regex_pattern_engine.client_id_line_parser.clone();
+}
+
+fn embedded_item_event_line_parser() -> String {
+    return EMBEDDED_DEFAULTS.regex_pattern_engine.item_event_line_parser.clone();
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 struct LogPatterns {
     world: String,
     total_kills_key: String,
     players_key: String,
     kills_key: String,
-    kill_by_means_key: String
+    kill_by_means_key: String,
+    #[serde(default = "embedded_provenance_key")]
+    provenance_key: String,
+    #[serde(default = "embedded_player_stats_key")]
+    player_stats_key: String,
+    #[serde(default = "embedded_rating_deltas_key")]
+    rating_deltas_key: String,
+    #[serde(default = "embedded_partial_key")]
+    partial_key: String
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 struct RegexPatterns {
     init_game_event: String,
     client_connect_event: String,
@@ -94,16 +235,23 @@ struct RegexPatterns {
     shutdown_event: String,
     exit_event: String,
     kill_event_line_parser: String,
-    user_info_line_parser: String
+    user_info_line_parser: String,
+    #[serde(default = "embedded_user_team_line_parser")]
+    user_team_line_parser: String,
+    #[serde(default = "embedded_game_type_line_parser")]
+    game_type_line_parser: String,
+    #[serde(default = "embedded_client_id_line_parser")]
+    client_id_line_parser: String,
+    #[serde(default = "embedded_item_event_line_parser")]
+    item_event_line_parser: String
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 struct OutputFormat {
     match_key: String
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 struct ErrorMessages {
-    invalid_kill_mean_token: String,
-    log_file_path_not_found: String
+    invalid_kill_mean_token: String
 }
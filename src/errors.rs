@@ -5,8 +5,18 @@ use std::error::Error;
 pub enum LogParserError {
     RegexParserError,
     ReadFileError,
+    LogFilePathError,
+    LogFilesPatternError,
+    UnsupportedCompressionError,
+    WriteError,
     SerializationError,
     StringfyError,
+    SchemaVersionError,
+    StorageError,
+    UnsupportedSourceError,
+    ConfigError(ConfigError),
+    StaticConfigError(StaticConfigError),
+    MalformedLineError { line_number: usize },
     UnexpectedError,
 }
 
@@ -16,8 +26,18 @@ impl fmt::Display for LogParserError {
         match self {
             LogParserError::RegexParserError => write!(f,"An error has happened on Regex Parsing Step..."),            
             LogParserError::ReadFileError => write!(f,"An error has happened Reading Log File..."),
+            LogParserError::LogFilePathError => write!(f,"No Log File Path Found..."),
+            LogParserError::LogFilesPatternError => write!(f,"No Log File matches the given Directory or Glob Pattern..."),
+            LogParserError::UnsupportedCompressionError => write!(f,"Log File Compression is not enabled in this build..."),
+            LogParserError::WriteError => write!(f,"An error has happened Writing Parsed Match..."),
             LogParserError::SerializationError => write!(f,"An error has happened on Serialization..."),
             LogParserError::StringfyError => write!(f,"An error has happened on Stringfication Process..."),
+            LogParserError::SchemaVersionError => write!(f,"Unsupported Report Schema Version..."),
+            LogParserError::StorageError => write!(f,"An error has happened Storing Parsed Matches..."),
+            LogParserError::UnsupportedSourceError => write!(f,"No Source Resolver is registered for the Job Source URI scheme..."),
+            LogParserError::ConfigError(error) => write!(f,"An error has happened Loading Config: {}", error),
+            LogParserError::StaticConfigError(error) => write!(f,"Invalid Static Config: {}", error),
+            LogParserError::MalformedLineError { line_number } => write!(f,"Malformed Log Line at line {}...", line_number),
             LogParserError::UnexpectedError => write!(f,"An unexpected error has happened on Log Parsing..."),
         }
     }
@@ -28,8 +48,18 @@ impl Error for LogParserError {
         match self {
             LogParserError::RegexParserError => "An error has happened on Regex Parsing Step...",
             LogParserError::ReadFileError => "The following Error {} has happened Reading Log File...",
+            LogParserError::LogFilePathError => "No Log File Path Found...",
+            LogParserError::LogFilesPatternError => "No Log File matches the given Directory or Glob Pattern...",
+            LogParserError::UnsupportedCompressionError => "Log File Compression is not enabled in this build...",
+            LogParserError::WriteError => "An error has happened Writing Parsed Match...",
             LogParserError::SerializationError => "An error has happened on Serialization...",
             LogParserError::StringfyError => "An error has happened on Stringfication Process...",
+            LogParserError::SchemaVersionError => "Unsupported Report Schema Version...",
+            LogParserError::StorageError => "An error has happened Storing Parsed Matches...",
+            LogParserError::UnsupportedSourceError => "No Source Resolver is registered for the Job Source URI scheme...",
+            LogParserError::ConfigError(_) => "An error has happened Loading Config...",
+            LogParserError::StaticConfigError(_) => "Invalid Static Config...",
+            LogParserError::MalformedLineError { .. } => "Malformed Log Line...",
             LogParserError::UnexpectedError => "An unexpected error has happened on Log Parsing...",
         }
     }
@@ -40,8 +70,18 @@ impl From<LogParserError> for &'static str {
         match error {
             LogParserError::RegexParserError => "LogParserError::RegexParserError",
             LogParserError::ReadFileError => "LogParserError::ReadFileError",
+            LogParserError::LogFilePathError => "LogParserError::LogFilePathError",
+            LogParserError::LogFilesPatternError => "LogParserError::LogFilesPatternError",
+            LogParserError::UnsupportedCompressionError => "LogParserError::UnsupportedCompressionError",
+            LogParserError::WriteError => "LogParserError::WriteError",
             LogParserError::SerializationError => "LogParserError::SerializationError",
             LogParserError::StringfyError => "LogParserError::StringfyError",
+            LogParserError::SchemaVersionError => "LogParserError::SchemaVersionError",
+            LogParserError::StorageError => "LogParserError::StorageError",
+            LogParserError::UnsupportedSourceError => "LogParserError::UnsupportedSourceError",
+            LogParserError::ConfigError(_) => "LogParserError::ConfigError",
+            LogParserError::StaticConfigError(_) => "LogParserError::StaticConfigError",
+            LogParserError::MalformedLineError { .. } => "LogParserError::MalformedLineError",
             LogParserError::UnexpectedError => "LogParserError::UnexpectedError",
         }
     }
@@ -52,9 +92,87 @@ impl From<LogParserError> for String {
         match error {
             LogParserError::RegexParserError => String::from("LogParserError::RegexParserError"),
             LogParserError::ReadFileError => String::from("LogParserError::ReadFileError"),
+            LogParserError::LogFilePathError => String::from("LogParserError::LogFilePathError"),
+            LogParserError::LogFilesPatternError => String::from("LogParserError::LogFilesPatternError"),
+            LogParserError::UnsupportedCompressionError => String::from("LogParserError::UnsupportedCompressionError"),
+            LogParserError::WriteError => String::from("LogParserError::WriteError"),
             LogParserError::SerializationError => String::from("LogParserError::SerializationError"),
             LogParserError::StringfyError => String::from("LogParserError::StringfyError"),
+            LogParserError::SchemaVersionError => String::from("LogParserError::SchemaVersionError"),
+            LogParserError::StorageError => String::from("LogParserError::StorageError"),
+            LogParserError::UnsupportedSourceError => String::from("LogParserError::UnsupportedSourceError"),
+            LogParserError::ConfigError(_) => String::from("LogParserError::ConfigError"),
+            LogParserError::StaticConfigError(_) => String::from("LogParserError::StaticConfigError"),
+            LogParserError::MalformedLineError { .. } => String::from("LogParserError::MalformedLineError"),
             LogParserError::UnexpectedError => String::from("LogParserError::UnexpectedError"),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Config loading error, naming the config key and the layer (file, environment variable
+/// or overrides) it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    ReadError { path: String },
+    UnsupportedFormat { path: String },
+    SyntaxError { source: String, message: String },
+    UnknownKey { source: String, key: String },
+    InvalidValue { source: String, key: String, message: String }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::ReadError { path } => write!(f, "Unable to read config file {}", path),
+            ConfigError::UnsupportedFormat { path } => write!(f, "Unsupported config file format: {}", path),
+            ConfigError::SyntaxError { source, message } => write!(f, "Invalid config at {}: {}", source, message),
+            ConfigError::UnknownKey { source, key } => write!(f, "Unknown config key `{}` at {}", key, source),
+            ConfigError::InvalidValue { source, key, message } => write!(f, "Invalid value for config key `{}` at {}: {}", key, source, message)
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl From<ConfigError> for LogParserError {
+    fn from(error: ConfigError) -> LogParserError {
+        return LogParserError::ConfigError(error);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidPattern {
+    pub key: String,
+    pub pattern: String,
+    pub reason: String
+}
+
+/// `STATIC_CONFIG.json` that can not be read or parsed, or every one of its invalid regex patterns.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StaticConfigError {
+    LoadError { path: String, message: String },
+    InvalidPatterns(Vec<InvalidPattern>)
+}
+
+impl fmt::Display for StaticConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StaticConfigError::LoadError { path, message } => write!(f, "Unable to load static config {}: {}", path, message),
+            StaticConfigError::InvalidPatterns(invalid_patterns) => {
+                write!(f, "Invalid regex patterns at static config:")?;
+                for invalid_pattern in invalid_patterns.iter() {
+                    write!(f, "\n  {} = {:?}: {}", invalid_pattern.key, invalid_pattern.pattern, invalid_pattern.reason)?;
+                }
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Error for StaticConfigError {}
+
+impl From<StaticConfigError> for LogParserError {
+    fn from(error: StaticConfigError) -> LogParserError {
+        return LogParserError::StaticConfigError(error);
+    }
+}
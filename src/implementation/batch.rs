@@ -0,0 +1,72 @@
+use std::path::{Path, PathBuf};
+
+use crate::errors::LogParserError;
+use crate::config::static_config::{STATIC_CONFIG, StaticConfigParameter};
+use crate::implementation::{
+    match_data::MatchData,
+    diagnostics::ParseDiagnostics
+};
+
+/// Matches and diagnostics of one log file.
+pub(crate) struct ParsedFile {
+    pub(crate) log_file: String,
+    pub(crate) matches: Vec<MatchData>,
+    pub(crate) diagnostics: ParseDiagnostics
+}
+
+/// Number of blocking threads to parse on, `0` meaning one per available CPU.
+pub(crate) fn worker_count(workers: usize) -> usize {
+    return match workers {
+        0 => std::thread::available_parallelism().map(|workers| workers.get()).unwrap_or(1),
+        workers => workers
+    };
+}
+
+pub(crate) fn resolve_log_files(pattern: &str) -> Result<Vec<PathBuf>, LogParserError> {
+
+    let mut log_files: Vec<PathBuf> = Vec::new();
+
+    if Path::new(pattern).is_dir() {
+        let entries = std::fs::read_dir(pattern).map_err(|_e| LogParserError::ReadFileError)?;
+
+        for entry in entries {
+            let path = entry.map_err(|_e| LogParserError::ReadFileError)?.path();
+
+            if path.is_file() {
+                log_files.push(path);
+            }
+        }
+    } else {
+        let paths = glob::glob(pattern).map_err(|_e| LogParserError::LogFilesPatternError)?;
+
+        for path in paths.flatten() {
+            if path.is_file() {
+                log_files.push(path);
+            }
+        }
+    }
+
+    if log_files.is_empty() {
+        return Err(LogParserError::LogFilesPatternError);
+    }
+
+    log_files.sort();
+
+    return Ok(log_files);
+}
+
+pub(crate) fn merge_matches(parsed_files: &[ParsedFile]) -> Vec<MatchData> {
+
+    let match_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::OutputMatchKey).to_string();
+    let mut merged_matches: Vec<MatchData> = Vec::new();
+
+    for parsed_file in parsed_files.iter() {
+        for file_match in parsed_file.matches.iter() {
+            let mut merged_match = file_match.clone();
+            merged_match.game_match = format!("{}_{}", match_key, merged_matches.len());
+            merged_matches.push(merged_match);
+        }
+    }
+
+    return merged_matches;
+}
@@ -1,4 +1,5 @@
-use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use serde_json::Value;
 
 use crate::death_causes::DeathCauses;
 
@@ -105,36 +106,38 @@ impl MatchKillMeans {
     }
 
     pub(crate) fn to_json(&self) -> Value {
-        return json!({
-            DeathCauses::Unknown.to_string(): &self.unknown,
-            DeathCauses::Shotgun.to_string(): &self.shotgun,
-            DeathCauses::Gauntlet.to_string(): &self.gauntlet,
-            DeathCauses::MachineGun.to_string(): &self.machine_gun,
-            DeathCauses::Grenade.to_string(): &self.grenade,
-            DeathCauses::GrenadeSplash.to_string(): &self.grenade_splash,
-            DeathCauses::Rocket.to_string(): &self.rocket,
-            DeathCauses::RocketSplash.to_string(): &self.rocket_splash,
-            DeathCauses::Plasma.to_string(): &self.plasma,
-            DeathCauses::PlasmaSplash.to_string(): &self.plasma_splash,
-            DeathCauses::Railgun.to_string(): &self.railgun,
-            DeathCauses::Lightning.to_string(): &self.lightning,
-            DeathCauses::Bfg.to_string(): &self.bfg,
-            DeathCauses::BfgSplash.to_string(): &self.bfg_splash,
-            DeathCauses::Water.to_string(): &self.water,
-            DeathCauses::Slime.to_string(): &self.slime,
-            DeathCauses::Lava.to_string(): &self.lava,
-            DeathCauses::Crush.to_string(): &self.crush,
-            DeathCauses::Telefrag.to_string(): &self.telefrag,
-            DeathCauses::Falling.to_string(): &self.falling,
-            DeathCauses::Suicide.to_string(): &self.suicide,
-            DeathCauses::TargetLaser.to_string(): &self.target_laser,
-            DeathCauses::TriggerHurt.to_string(): &self.trigger_hurt,
-            DeathCauses::Nail.to_string(): &self.nail,
-            DeathCauses::Chaingun.to_string(): &self.chaingun,
-            DeathCauses::ProximityMine.to_string(): &self.proximity_mine,
-            DeathCauses::Kamikaze.to_string(): &self.kamikaze,
-            DeathCauses::Juiced.to_string(): &self.juiced,
-            DeathCauses::Grapple.to_string(): &self.grapple
-        });
-    }  
+        let mut kill_means: BTreeMap<String, usize> = BTreeMap::new();
+
+        kill_means.insert(DeathCauses::Unknown.to_string(), self.unknown);
+        kill_means.insert(DeathCauses::Shotgun.to_string(), self.shotgun);
+        kill_means.insert(DeathCauses::Gauntlet.to_string(), self.gauntlet);
+        kill_means.insert(DeathCauses::MachineGun.to_string(), self.machine_gun);
+        kill_means.insert(DeathCauses::Grenade.to_string(), self.grenade);
+        kill_means.insert(DeathCauses::GrenadeSplash.to_string(), self.grenade_splash);
+        kill_means.insert(DeathCauses::Rocket.to_string(), self.rocket);
+        kill_means.insert(DeathCauses::RocketSplash.to_string(), self.rocket_splash);
+        kill_means.insert(DeathCauses::Plasma.to_string(), self.plasma);
+        kill_means.insert(DeathCauses::PlasmaSplash.to_string(), self.plasma_splash);
+        kill_means.insert(DeathCauses::Railgun.to_string(), self.railgun);
+        kill_means.insert(DeathCauses::Lightning.to_string(), self.lightning);
+        kill_means.insert(DeathCauses::Bfg.to_string(), self.bfg);
+        kill_means.insert(DeathCauses::BfgSplash.to_string(), self.bfg_splash);
+        kill_means.insert(DeathCauses::Water.to_string(), self.water);
+        kill_means.insert(DeathCauses::Slime.to_string(), self.slime);
+        kill_means.insert(DeathCauses::Lava.to_string(), self.lava);
+        kill_means.insert(DeathCauses::Crush.to_string(), self.crush);
+        kill_means.insert(DeathCauses::Telefrag.to_string(), self.telefrag);
+        kill_means.insert(DeathCauses::Falling.to_string(), self.falling);
+        kill_means.insert(DeathCauses::Suicide.to_string(), self.suicide);
+        kill_means.insert(DeathCauses::TargetLaser.to_string(), self.target_laser);
+        kill_means.insert(DeathCauses::TriggerHurt.to_string(), self.trigger_hurt);
+        kill_means.insert(DeathCauses::Nail.to_string(), self.nail);
+        kill_means.insert(DeathCauses::Chaingun.to_string(), self.chaingun);
+        kill_means.insert(DeathCauses::ProximityMine.to_string(), self.proximity_mine);
+        kill_means.insert(DeathCauses::Kamikaze.to_string(), self.kamikaze);
+        kill_means.insert(DeathCauses::Juiced.to_string(), self.juiced);
+        kill_means.insert(DeathCauses::Grapple.to_string(), self.grapple);
+
+        return serde_json::to_value(kill_means).unwrap_or(Value::Null);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,127 @@
+use std::fmt;
+use std::str::FromStr;
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+
+/// Offending lines kept in a report, unless parsing on `ParseMode::Collect` keeps them all.
+pub const DIAGNOSTIC_LINES_LIMIT: usize = 10;
+
+/// How parsing reacts to a log line it can not use.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseMode {
+    /// Fails on the first malformed line with `LogParserError::MalformedLineError`.
+    Strict,
+    /// Sends a warning callback for each unusable line and goes on.
+    #[default]
+    Lenient,
+    /// Goes on past every unusable line and lists them all in the report.
+    Collect
+}
+
+impl fmt::Display for ParseMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseMode::Strict => write!(f, "strict"),
+            ParseMode::Lenient => write!(f, "lenient"),
+            ParseMode::Collect => write!(f, "collect")
+        }
+    }
+}
+
+impl FromStr for ParseMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "strict" => Ok(ParseMode::Strict),
+            "lenient" => Ok(ParseMode::Lenient),
+            "collect" => Ok(ParseMode::Collect),
+            _ => Err(format!("Invalid Parse Mode: {}", s))
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    /// No known event, as separators, chat and end of match scores.
+    UnknownLine,
+    /// Not valid UTF-8.
+    InvalidEncoding,
+    /// A known event whose payload does not match its line parser regex.
+    MalformedEvent,
+    /// A kill by a weapon that is not a known `MOD_*` death cause.
+    UnknownDeathCause,
+    /// A kill of, or by, a player with no `ClientUserinfoChanged` line in the match.
+    KillByUnknownPlayer,
+    /// A kill after a `ShutdownGame` and before the next `InitGame`.
+    KillOutsideMatch,
+    /// An event before the first `InitGame`, discarded as `parse_rules.orphan_events` says.
+    OrphanedEvent
+}
+
+impl DiagnosticKind {
+    /// Lines that look corrupted, the only ones strict parsing fails on.
+    pub fn is_malformed(&self) -> bool {
+        match self {
+            DiagnosticKind::InvalidEncoding | DiagnosticKind::MalformedEvent | DiagnosticKind::UnknownDeathCause => true,
+            DiagnosticKind::UnknownLine | DiagnosticKind::KillByUnknownPlayer | DiagnosticKind::KillOutsideMatch | DiagnosticKind::OrphanedEvent => false
+        }
+    }
+
+    /// Lines that parse, but whose match data can not be trusted.
+    pub fn is_match_inconsistency(&self) -> bool {
+        return matches!(self, DiagnosticKind::KillByUnknownPlayer | DiagnosticKind::KillOutsideMatch);
+    }
+}
+
+/// A log line the parser could not use.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseDiagnostic {
+    pub line_number: usize,
+    pub kind: DiagnosticKind,
+    pub line: String
+}
+
+/// A match with inconsistent lines, counted by kind.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchInconsistencies {
+    #[serde(rename = "match")]
+    pub game_match: String,
+    pub first_line: usize,
+    pub last_line: usize,
+    pub counts: BTreeMap<DiagnosticKind, usize>
+}
+
+/// The diagnostics section of a report, so corrupted logs can be spotted without a warning callback.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParseDiagnostics {
+    pub counts: BTreeMap<DiagnosticKind, usize>,
+    /// The first `DIAGNOSTIC_LINES_LIMIT` offending lines, or all of them on `ParseMode::Collect`.
+    pub lines: Vec<ParseDiagnostic>,
+    pub inconsistent_matches: Vec<MatchInconsistencies>
+}
+
+impl ParseDiagnostics {
+    pub(crate) fn record(&mut self, diagnostic: ParseDiagnostic, keep_all_lines: bool) {
+
+        *self.counts.entry(diagnostic.kind).or_insert(0) += 1;
+
+        if keep_all_lines || self.lines.len() < DIAGNOSTIC_LINES_LIMIT {
+            self.lines.push(diagnostic);
+        }
+    }
+
+    /// Adds the counts and lines of a later chunk of the same log. Its matches are published
+    /// again under their final labels, so its inconsistent matches are left out.
+    pub(crate) fn merge_chunk(&mut self, chunk_diagnostics: ParseDiagnostics, keep_all_lines: bool) {
+
+        for (kind, count) in chunk_diagnostics.counts {
+            *self.counts.entry(kind).or_insert(0) += count;
+        }
+
+        let free_lines = if keep_all_lines { usize::MAX } else { DIAGNOSTIC_LINES_LIMIT.saturating_sub(self.lines.len()) };
+        self.lines.extend(chunk_diagnostics.lines.into_iter().take(free_lines));
+    }
+}
@@ -0,0 +1,26 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A player name, weapon or item, shared by every match data entry that mentions it.
+pub(crate) type Name = Arc<str>;
+
+/// Allocates each distinct name once per parser, so matches only clone reference counts
+/// instead of allocating new `String` keys for every kill.
+#[derive(Default)]
+pub(crate) struct NameInterner {
+    names: HashSet<Name>
+}
+
+impl NameInterner {
+    pub(crate) fn intern(&mut self, name: &str) -> Name {
+
+        if let Some(interned_name) = self.names.get(name) {
+            return interned_name.clone();
+        }
+
+        let interned_name: Name = Arc::from(name);
+        self.names.insert(interned_name.clone());
+
+        return interned_name;
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::io::BufRead;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use crate::errors::LogParserError;
+use crate::interface::JobQueue;
+use crate::config::{
+    config::ConfigValue,
+    dynamic_config::{CONFIG, ConfigParameter}
+};
+use crate::implementation::{
+    log_parser::ConcreteLogParser,
+    log_reader::open_log_file
+};
+use crate::service::LogParser;
+
+pub const FILE_SCHEME: &str = "file";
+
+/// Opens the log addressed by a job source URI.
+pub type JobSourceResolver = dyn Fn(&str) -> Result<Box<dyn BufRead>, LogParserError> + Send + Sync;
+
+/// A request to parse one log, as carried by a message queue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseJob {
+    pub job_id: String,
+    /// `file:///path/to/games.log`, a plain path, or any scheme with a registered resolver.
+    pub source_uri: String,
+    /// Partial config json merged on top of the worker config for this job only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_overrides: Option<Value>
+}
+
+impl ParseJob {
+    pub fn new(job_id: &str, source_uri: &str) -> Self {
+        Self {
+            job_id: String::from(job_id),
+            source_uri: String::from(source_uri),
+            config_overrides: None
+        }
+    }
+
+    pub fn with_config_overrides(mut self, config_overrides: Value) -> Self {
+        self.config_overrides = Some(config_overrides);
+        return self;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ParseOutcome {
+    Succeeded { report: String },
+    Failed { error: String }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseResult {
+    pub job_id: String,
+    pub source_uri: String,
+    #[serde(flatten)]
+    pub outcome: ParseOutcome
+}
+
+/// Job queue kept in memory, mostly meant for tests and single process pipelines.
+///
+/// Clones share the same queue, so jobs can be pushed and results read while a worker owns it.
+/// `next_job` returns `None` as soon as no job is pending.
+#[derive(Clone, Default)]
+pub struct InMemoryJobQueue {
+    jobs: Arc<Mutex<VecDeque<ParseJob>>>,
+    results: Arc<Mutex<Vec<ParseResult>>>
+}
+
+impl InMemoryJobQueue {
+    pub fn new() -> Self {
+        return InMemoryJobQueue::default();
+    }
+
+    pub fn push_job(&self, job: ParseJob) {
+        self.jobs.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push_back(job);
+    }
+
+    pub fn results(&self) -> Vec<ParseResult> {
+        return self.results.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    }
+}
+
+impl JobQueue for InMemoryJobQueue {
+    fn next_job(&mut self) -> Pin<Box<dyn Future<Output = Result<Option<ParseJob>, LogParserError>> + '_>> {
+        let job = self.jobs.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).pop_front();
+        return Box::pin(async move { Ok(job) });
+    }
+
+    fn publish_result(&mut self, result: ParseResult) -> Pin<Box<dyn Future<Output = Result<(), LogParserError>> + '_>> {
+        self.results.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(result);
+        return Box::pin(async move { Ok(()) });
+    }
+}
+
+/// Consumes parse jobs from a queue and publishes one result per job.
+///
+/// Jobs are parsed with the config of the thread running the worker, plus the job overrides.
+/// A failed job is published as a failed result, only queue errors stop the worker.
+pub struct JobWorker {
+    queue: Box<dyn JobQueue>,
+    source_resolvers: BTreeMap<String, Box<JobSourceResolver>>
+}
+
+impl JobWorker {
+    pub fn new(queue: Box<dyn JobQueue>) -> Self {
+
+        let mut source_resolvers: BTreeMap<String, Box<JobSourceResolver>> = BTreeMap::new();
+
+        source_resolvers.insert(String::from(FILE_SCHEME), Box::new(|source_uri: &str| {
+            let file_scheme_prefix = format!("{}://", FILE_SCHEME);
+            return open_log_file(source_uri.strip_prefix(file_scheme_prefix.as_str()).unwrap_or(source_uri));
+        }));
+
+        Self {
+            queue,
+            source_resolvers
+        }
+    }
+
+    /// Resolvers receive the full source URI, e.g. `s3://bucket/games.log` for the `s3` scheme.
+    pub fn register_source_resolver(&mut self, scheme: &str, resolver: Box<JobSourceResolver>) {
+        self.source_resolvers.insert(String::from(scheme), resolver);
+    }
+
+    /// Runs until the queue is drained, returning how many jobs were processed.
+    pub async fn run(&mut self) -> Result<usize, LogParserError> {
+
+        let mut jobs_processed: usize = 0;
+
+        while let Some(job) = self.queue.next_job().await? {
+            let result = self.process_job(job).await;
+            self.queue.publish_result(result).await?;
+            jobs_processed += 1;
+        }
+
+        return Ok(jobs_processed);
+    }
+
+    pub async fn process_job(&self, job: ParseJob) -> ParseResult {
+
+        let outcome = match self.parse_job(&job).await {
+            Ok(report) => ParseOutcome::Succeeded { report },
+            Err(err) => ParseOutcome::Failed { error: err.to_string() }
+        };
+
+        return ParseResult {
+            job_id: job.job_id,
+            source_uri: job.source_uri,
+            outcome
+        };
+    }
+
+    async fn parse_job(&self, job: &ParseJob) -> Result<String, LogParserError> {
+
+        let reader = self.open_source(&job.source_uri)?;
+
+        let worker_config = CONFIG.with(|config| config.borrow().clone());
+
+        let mut job_config = match &job.config_overrides {
+            Some(config_overrides) => worker_config.with_overrides(config_overrides)?,
+            None => worker_config.clone()
+        };
+
+        // The result carries the whole report, so matches are never streamed elsewhere.
+        job_config.set_parameter(ConfigParameter::StreamMatches, ConfigValue::Bool(false));
+
+        job_config.install();
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        let report = log_parser_service.parse_log(reader, Some(job.source_uri.clone())).await;
+
+        worker_config.install();
+
+        return report;
+    }
+
+    fn open_source(&self, source_uri: &str) -> Result<Box<dyn BufRead>, LogParserError> {
+
+        let scheme = match source_uri.split_once("://") {
+            Some((scheme, _location)) => scheme,
+            None => FILE_SCHEME
+        };
+
+        let resolver = self.source_resolvers.get(scheme).ok_or(LogParserError::UnsupportedSourceError)?;
+
+        return resolver(source_uri);
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, BTreeSet};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value};
+
+use crate::errors::LogParserError;
+use crate::config::static_config::{STATIC_CONFIG, StaticConfigParameter};
+
+static COLOR_CODE_REGEX: Lazy<Regex> = Lazy::new(|| { Regex::new(r"\^[0-9a-zA-Z]").unwrap() });
+
+/// Rules used to decide which player names belong to the same person.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IdentityRules {
+    #[serde(default)]
+    pub case_insensitive: bool,
+    #[serde(default)]
+    pub strip_color_codes: bool,
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>
+}
+
+impl IdentityRules {
+    pub fn canonical_name(&self, player: &str) -> String {
+
+        let mut name = if self.strip_color_codes {
+            COLOR_CODE_REGEX.replace_all(player, "").to_string()
+        } else {
+            String::from(player)
+        };
+
+        if let Some(alias) = self.aliases.iter().find(|(alias, _)| self.same_name(alias, &name)) {
+            name = alias.1.clone();
+        }
+
+        if self.case_insensitive {
+            name = name.to_lowercase();
+        }
+
+        return name;
+    }
+
+    fn same_name(&self, a: &str, b: &str) -> bool {
+        if self.case_insensitive {
+            return a.to_lowercase() == b.to_lowercase();
+        }
+        return a == b;
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlayerLifetimeStats {
+    pub player: String,
+    pub names: BTreeSet<String>,
+    pub matches_played: usize,
+    pub wins: usize,
+    pub score: i64,
+    pub frags: usize,
+    pub deaths: usize,
+    pub best_streak: usize,
+    pub favorite_weapon: Option<String>,
+    #[serde(skip)]
+    weapons: BTreeMap<String, usize>
+}
+
+/// Folds parsed match reports into lifetime player stats.
+///
+/// Frags, deaths, streaks and weapons come from the per player stats of each match,
+/// so reports should be produced with `show_player_stats` enabled.
+pub struct Leaderboard {
+    identity_rules: IdentityRules,
+    players: BTreeMap<String, PlayerLifetimeStats>
+}
+
+impl Leaderboard {
+    pub fn new(identity_rules: IdentityRules) -> Self {
+        Self {
+            identity_rules,
+            players: BTreeMap::new()
+        }
+    }
+
+    /// Accepts a plain matches array, a report envelope or a batch report.
+    pub fn fold_report(&mut self, report: &Value) -> Result<(), LogParserError> {
+        match report {
+            Value::Array(matches) => {
+                for game_match in matches.iter() {
+                    self.fold_match(game_match)?;
+                }
+                return Ok(());
+            },
+            Value::Object(report) => {
+                if let Some(merged) = report.get("merged") {
+                    return self.fold_report(merged);
+                } else if let Some(matches) = report.get("matches") {
+                    return self.fold_report(matches);
+                }
+                return Err(LogParserError::SerializationError);
+            },
+            _ => Err(LogParserError::SerializationError)
+        }
+    }
+
+    pub fn fold_match(&mut self, game_match: &Value) -> Result<(), LogParserError> {
+
+        let match_stats = game_match.as_object()
+            .and_then(|game_match| game_match.values().next())
+            .and_then(|match_stats| match_stats.as_object())
+            .ok_or(LogParserError::SerializationError)?;
+
+        let empty = Map::new();
+        let players = match_stats.get(&STATIC_CONFIG.get_parameter(StaticConfigParameter::PlayersKey).to_string()).and_then(|players| players.as_array());
+        let kills = match_stats.get(&STATIC_CONFIG.get_parameter(StaticConfigParameter::KillsKey).to_string()).and_then(|kills| kills.as_object()).unwrap_or(&empty);
+        let player_stats = match_stats.get(&STATIC_CONFIG.get_parameter(StaticConfigParameter::PlayerStatsKey).to_string()).and_then(|player_stats| player_stats.as_object()).unwrap_or(&empty);
+
+        let mut match_players: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+        for player in players.into_iter().flatten().filter_map(|player| player.as_str()) {
+            match_players.entry(self.identity_rules.canonical_name(player)).or_default().insert(String::from(player));
+        }
+
+        for player in kills.keys().chain(player_stats.keys()) {
+            match_players.entry(self.identity_rules.canonical_name(player)).or_default().insert(player.clone());
+        }
+
+        let mut match_scores: BTreeMap<String, i64> = BTreeMap::new();
+
+        for (player, score) in kills.iter() {
+            *match_scores.entry(self.identity_rules.canonical_name(player)).or_insert(0) += score.as_i64().unwrap_or(0);
+        }
+
+        let best_score = match_scores.values().max().copied();
+
+        for (canonical_name, names) in match_players.into_iter() {
+
+            let lifetime_stats = self.players.entry(canonical_name.clone()).or_insert_with(|| PlayerLifetimeStats {
+                player: canonical_name.clone(),
+                ..Default::default()
+            });
+
+            let score = match_scores.get(&canonical_name).copied().unwrap_or(0);
+
+            lifetime_stats.matches_played += 1;
+            lifetime_stats.score += score;
+
+            if best_score == Some(score) && match_scores.contains_key(&canonical_name) {
+                lifetime_stats.wins += 1;
+            }
+
+            for name in names.iter() {
+                if let Some(stats) = player_stats.get(name) {
+                    lifetime_stats.frags += stats["kills"].as_u64().unwrap_or(0) as usize;
+                    lifetime_stats.deaths += stats["deaths"].as_u64().unwrap_or(0) as usize;
+                    lifetime_stats.best_streak = lifetime_stats.best_streak.max(stats["best_streak"].as_u64().unwrap_or(0) as usize);
+
+                    for (weapon, count) in stats["kill_by_means"].as_object().into_iter().flatten() {
+                        *lifetime_stats.weapons.entry(weapon.clone()).or_insert(0) += count.as_u64().unwrap_or(0) as usize;
+                    }
+                }
+            }
+
+            lifetime_stats.favorite_weapon = lifetime_stats.weapons.iter()
+                .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                .map(|(weapon, _)| weapon.clone());
+
+            lifetime_stats.names.extend(names);
+        }
+
+        return Ok(());
+    }
+
+    /// Players ranked by wins, then score, then name.
+    pub fn ranking(&self) -> Vec<&PlayerLifetimeStats> {
+
+        let mut ranking: Vec<&PlayerLifetimeStats> = self.players.values().collect();
+
+        ranking.sort_by(|a, b| {
+            b.wins.cmp(&a.wins)
+                .then_with(|| b.score.cmp(&a.score))
+                .then_with(|| a.player.cmp(&b.player))
+        });
+
+        return ranking;
+    }
+
+    pub fn to_json(&self) -> Result<Value, LogParserError> {
+        return serde_json::to_value(self.ranking()).map_err(|_e| LogParserError::SerializationError);
+    }
+}
@@ -0,0 +1,85 @@
+use std::io::{BufRead, Cursor};
+
+use crate::errors::LogParserError;
+use crate::implementation::log_event::LogEvent;
+
+/// Chunks are cut at the first `InitGame` line past this size, so each one holds whole matches.
+pub(crate) const LOG_CHUNK_TARGET_BYTES: usize = 4 * 1024 * 1024;
+
+/// Consecutive whole matches of a log, and the line number of their first line.
+pub(crate) struct LogChunk {
+    pub(crate) first_line: usize,
+    pub(crate) bytes: Vec<u8>
+}
+
+impl LogChunk {
+    pub(crate) fn reader(self) -> Box<dyn BufRead> {
+        return Box::new(Cursor::new(self.bytes));
+    }
+}
+
+/// Splits a log at match boundaries while reading it, holding a single chunk in memory.
+///
+/// Lines before the first `InitGame` stay in the first chunk, as they belong to the first match.
+pub(crate) struct LogChunks {
+    reader: Box<dyn BufRead>,
+    target_bytes: usize,
+    next_line: usize,
+    pending_line: Option<Vec<u8>>,
+    match_started: bool
+}
+
+impl LogChunks {
+    pub(crate) fn new(reader: Box<dyn BufRead>, target_bytes: usize) -> Self {
+        Self {
+            reader,
+            target_bytes,
+            next_line: 1,
+            pending_line: None,
+            match_started: false
+        }
+    }
+
+    pub(crate) fn next_chunk(&mut self) -> Result<Option<LogChunk>, LogParserError> {
+
+        let first_line = self.next_line;
+        let mut bytes: Vec<u8> = Vec::new();
+        let mut line: Vec<u8> = Vec::new();
+
+        if let Some(pending_line) = self.pending_line.take() {
+            bytes = pending_line;
+            self.next_line += 1;
+        }
+
+        loop {
+
+            line.clear();
+
+            if self.reader.read_until(b'\n', &mut line).map_err(|_e| LogParserError::ReadFileError)? == 0 {
+                break;
+            }
+
+            if bytes.len() >= self.target_bytes && self.match_started && is_match_boundary(&line) {
+                self.pending_line = Some(line);
+                break;
+            }
+
+            if !self.match_started {
+                self.match_started = is_match_boundary(&line);
+            }
+
+            bytes.extend_from_slice(&line);
+            self.next_line += 1;
+        }
+
+        if bytes.is_empty() {
+            return Ok(None);
+        }
+
+        return Ok(Some(LogChunk { first_line, bytes }));
+    }
+}
+
+fn is_match_boundary(line: &[u8]) -> bool {
+    return std::str::from_utf8(line).is_ok_and(|line| LogEvent::detect_line_log_event(line).ok() == Some(LogEvent::InitMatch));
+}
@@ -1,21 +1,33 @@
-use regex::Regex;
+use regex::{Regex, RegexSet};
 use once_cell::sync::Lazy;
 
-use crate::config::static_config::{STATIC_CONFIG, StaticConfigParameter};
+use crate::config::static_config::{STATIC_CONFIG, StaticConfig, StaticConfigParameter};
 use crate::errors::LogParserError;
+use crate::implementation::match_event::event_time;
 
-pub static INIT_GAME_EVENT_DETECT_REGEX: Lazy<Regex> = Lazy::new(|| { Regex::new(STATIC_CONFIG.get_parameter(StaticConfigParameter::InitGameEventRegex).to_string().as_str()).unwrap() });
-pub static CLIENT_CONNECT_EVENT_DETECT_REGEX: Lazy<Regex> = Lazy::new(|| { Regex::new(STATIC_CONFIG.get_parameter(StaticConfigParameter::ClientConnectEventRegex).to_string().as_str()).unwrap() });
-pub static CLIENT_INFO_CHANGE_EVENT_DETECT_REGEX: Lazy<Regex> = Lazy::new(|| { Regex::new(STATIC_CONFIG.get_parameter(StaticConfigParameter::ClientInfoChangeEventRegex).to_string().as_str()).unwrap() });
-pub static CLIENT_BEGIN_EVENT_DETECT_REGEX: Lazy<Regex> = Lazy::new(|| { Regex::new(STATIC_CONFIG.get_parameter(StaticConfigParameter::ClientBeginEventRegex).to_string().as_str()).unwrap() });
-pub static CLIENT_DISCONNECT_EVENT_DETECT_REGEX: Lazy<Regex> = Lazy::new(|| { Regex::new(STATIC_CONFIG.get_parameter(StaticConfigParameter::ClientDisconnectEventRegex).to_string().as_str()).unwrap() });
-pub static ITEM_EVENT_DETECT_REGEX: Lazy<Regex> = Lazy::new(|| { Regex::new(STATIC_CONFIG.get_parameter(StaticConfigParameter::ItemEventRegex).to_string().as_str()).unwrap() });
-pub static KILL_EVENT_DETECT_REGEX: Lazy<Regex> = Lazy::new(|| { Regex::new(STATIC_CONFIG.get_parameter(StaticConfigParameter::KillEventRegex).to_string().as_str()).unwrap() });
-pub static SHUTDOWN_EVENT_DETECT_REGEX: Lazy<Regex> = Lazy::new(|| { Regex::new(STATIC_CONFIG.get_parameter(StaticConfigParameter::ShutdownEventRegex).to_string().as_str()).unwrap() });
-pub static EXIT_EVENT_DETECT_REGEX: Lazy<Regex> = Lazy::new(|| { Regex::new(STATIC_CONFIG.get_parameter(StaticConfigParameter::ExitEventRegex).to_string().as_str()).unwrap() });
-pub static KILL_PARSER_REGEX: Lazy<Regex> = Lazy::new(|| { Regex::new(STATIC_CONFIG.get_parameter(StaticConfigParameter::KillEventLineParserRegex).to_string().as_str()).unwrap() });
-pub static USER_INFO_PARSER_REGEX: Lazy<Regex> = Lazy::new(|| { Regex::new(STATIC_CONFIG.get_parameter(StaticConfigParameter::UserInfoLineParserRegex).to_string().as_str()).unwrap() });
+/// Matches no log line, stands in for a static config pattern that does not compile.
+const NO_MATCH_PATTERN: &str = r"[^\s\S]";
 
+/// `validate_static_config` reports invalid patterns before parsing starts, so an invalid one
+/// only falls back to a regex that matches nothing instead of panicking.
+fn static_config_regex(parameter: StaticConfigParameter) -> Regex {
+    return Regex::new(STATIC_CONFIG.get_parameter(parameter).to_string().as_str())
+        .unwrap_or_else(|_err| Regex::new(NO_MATCH_PATTERN).unwrap());
+}
+
+pub static KILL_PARSER_REGEX: Lazy<Regex> = Lazy::new(|| static_config_regex(StaticConfigParameter::KillEventLineParserRegex));
+pub static USER_INFO_PARSER_REGEX: Lazy<Regex> = Lazy::new(|| static_config_regex(StaticConfigParameter::UserInfoLineParserRegex));
+pub static USER_TEAM_PARSER_REGEX: Lazy<Regex> = Lazy::new(|| static_config_regex(StaticConfigParameter::UserTeamLineParserRegex));
+pub static CLIENT_ID_PARSER_REGEX: Lazy<Regex> = Lazy::new(|| static_config_regex(StaticConfigParameter::ClientIdLineParserRegex));
+pub static ITEM_PARSER_REGEX: Lazy<Regex> = Lazy::new(|| static_config_regex(StaticConfigParameter::ItemEventLineParserRegex));
+pub static GAME_TYPE_PARSER_REGEX: Lazy<Regex> = Lazy::new(|| static_config_regex(StaticConfigParameter::GameTypeLineParserRegex));
+
+/// Whether a log line is one of the events the parser understands.
+pub fn is_known_log_line(log_line: &str) -> bool {
+    return LogEvent::detect_line_log_event(log_line).is_ok();
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub(crate) enum LogEvent {
     InitMatch,
     ClientConnect,
@@ -28,28 +40,174 @@ pub(crate) enum LogEvent {
     Exit
 }
 
+/// Detected events in detection priority order, with the static config key of their detection pattern.
+const DETECTED_LOG_EVENTS: [(StaticConfigParameter, LogEvent); 9] = [
+    (StaticConfigParameter::ItemEventRegex, LogEvent::Item),
+    (StaticConfigParameter::KillEventRegex, LogEvent::Kill),
+    (StaticConfigParameter::ClientInfoChangeEventRegex, LogEvent::ClientUserinfoChanged),
+    (StaticConfigParameter::ClientConnectEventRegex, LogEvent::ClientConnect),
+    (StaticConfigParameter::ClientBeginEventRegex, LogEvent::ClientBegin),
+    (StaticConfigParameter::ClientDisconnectEventRegex, LogEvent::ClientDisconnect),
+    (StaticConfigParameter::InitGameEventRegex, LogEvent::InitMatch),
+    (StaticConfigParameter::ShutdownEventRegex, LogEvent::ShutdownGame),
+    (StaticConfigParameter::ExitEventRegex, LogEvent::Exit)
+];
+
+/// Event detection built from the static config `*_event` patterns.
+pub(crate) struct LogEventDetector {
+    /// Event token of each pattern that is a whole word, as the default `\bKill\b`.
+    event_tokens: [Option<String>; 9],
+    /// All detection patterns in one automaton, in `DETECTED_LOG_EVENTS` order.
+    regex_set: RegexSet
+}
+
+static LOG_EVENT_DETECTOR: Lazy<LogEventDetector> = Lazy::new(|| LogEventDetector::new(&STATIC_CONFIG));
+
+/// `Kill` for `\bKill\b`, or `None` for a pattern that is not a single whole word.
+fn pattern_event_token(pattern: &str) -> Option<String> {
+
+    let event_token = pattern.strip_prefix(r"\b")?.strip_suffix(r"\b")?;
+
+    if event_token.is_empty() || !event_token.bytes().all(|byte| byte.is_ascii_alphanumeric() || byte == b'_') {
+        return None;
+    }
+
+    return Some(String::from(event_token));
+}
+
+/// `Kill` for `" 21:42 Kill: 1022 2 22: ..."`, or `None` when the line has no `M:SS Event:` prefix.
+fn event_token(log_line: &str) -> Option<&str> {
+
+    let (timestamp, event) = log_line.trim_start().split_once(' ')?;
+    let (minutes, seconds) = timestamp.split_once(':')?;
+
+    if minutes.is_empty() || seconds.is_empty() || !minutes.bytes().chain(seconds.bytes()).all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    return event.split_once(':').map(|(event_token, _rest)| event_token);
+}
+
+impl LogEventDetector {
+    pub(crate) fn new(static_config: &StaticConfig) -> Self {
+
+        let patterns = DETECTED_LOG_EVENTS.map(|(parameter, _log_event)| static_config.get_parameter(parameter).to_string());
+
+        return LogEventDetector {
+            event_tokens: patterns.clone().map(|pattern| pattern_event_token(&pattern)),
+            regex_set: RegexSet::new(patterns).unwrap_or_else(|_err| RegexSet::empty())
+        };
+    }
+
+    /// A standard `M:SS Event:` line whose event token is the word of a detection pattern is
+    /// classified without running any regex. Other lines fall back to a single `RegexSet` scan
+    /// of the configured patterns, where the first match in `DETECTED_LOG_EVENTS` order wins.
+    pub(crate) fn detect(&self, log_line: &str) -> Result<LogEvent, LogParserError> {
+
+        let token_event = event_token(log_line).and_then(|event_token| {
+            self.event_tokens.iter().position(|pattern_token| pattern_token.as_deref() == Some(event_token))
+        });
+
+        if let Some(event_index) = token_event {
+            return Ok(DETECTED_LOG_EVENTS[event_index].1);
+        }
+
+        return self.regex_set.matches(log_line).iter().next()
+            .map(|event_index| DETECTED_LOG_EVENTS[event_index].1)
+            .ok_or(LogParserError::RegexParserError);
+    }
+}
+
 impl LogEvent {
     pub(crate) fn detect_line_log_event(log_line: &str) -> Result<Self, LogParserError> {
-         if ITEM_EVENT_DETECT_REGEX.is_match(log_line) {
-            return Ok(LogEvent::Item);
-         } else if KILL_EVENT_DETECT_REGEX.is_match(log_line) {
-            return Ok(LogEvent::Kill);
-         } else if CLIENT_INFO_CHANGE_EVENT_DETECT_REGEX.is_match(log_line){
-             return Ok(LogEvent::ClientUserinfoChanged);
-         } else if CLIENT_CONNECT_EVENT_DETECT_REGEX.is_match(log_line) {
-            return Ok(LogEvent::ClientConnect);
-         } else if CLIENT_BEGIN_EVENT_DETECT_REGEX.is_match(log_line) {
-            return Ok(LogEvent::ClientBegin);
-         } else if CLIENT_DISCONNECT_EVENT_DETECT_REGEX.is_match(log_line) {
-            return Ok(LogEvent::ClientDisconnect);
-         } else if INIT_GAME_EVENT_DETECT_REGEX.is_match(log_line) {
-            return Ok(LogEvent::InitMatch);
-         } else if SHUTDOWN_EVENT_DETECT_REGEX.is_match(log_line) {
-            return Ok(LogEvent::ShutdownGame);
-         } else if EXIT_EVENT_DETECT_REGEX.is_match(log_line) {
-            return Ok(LogEvent::Exit);
-         } else {
-            return Err(LogParserError::RegexParserError)
-         }
+        return LOG_EVENT_DETECTOR.detect(log_line);
+    }
+}
+
+/// A detected log line with its payload borrowed from the line, so parsing it allocates nothing.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum LogLineEvent<'a> {
+    InitMatch {
+        game_type: Option<u32>
+    },
+    ClientConnect,
+    ClientUserinfoChanged {
+        player: &'a str,
+        client_id: Option<u32>,
+        team: Option<u32>
+    },
+    ClientBegin,
+    ClientDisconnect,
+    /// Item lines are the bulk of a log, their payload is only parsed on demand by `item_pickup`.
+    Item {
+        log_line: &'a str
+    },
+    Kill {
+        time_seconds: u32,
+        killer: &'a str,
+        victim: &'a str,
+        weapon: &'a str
+    },
+    ShutdownGame,
+    Exit
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ItemPickup<'a> {
+    pub(crate) time_seconds: u32,
+    pub(crate) client_id: Option<u32>,
+    pub(crate) item: &'a str
+}
+
+impl<'a> LogLineEvent<'a> {
+    pub(crate) fn parse(log_event: LogEvent, log_line: &'a str) -> Result<Self, LogParserError> {
+        match log_event {
+            LogEvent::InitMatch => {
+                let game_type = GAME_TYPE_PARSER_REGEX.captures(log_line).and_then(|captures| captures.get(1)?.as_str().parse::<u32>().ok());
+                return Ok(LogLineEvent::InitMatch { game_type });
+            },
+            LogEvent::ClientConnect => Ok(LogLineEvent::ClientConnect),
+            LogEvent::ClientUserinfoChanged => {
+                let captures = USER_INFO_PARSER_REGEX.captures(log_line).ok_or(LogParserError::RegexParserError)?;
+
+                return Ok(LogLineEvent::ClientUserinfoChanged {
+                    player: captures.get(1).map_or("", |player| player.as_str()),
+                    client_id: CLIENT_ID_PARSER_REGEX.captures(log_line).and_then(|captures| captures.get(1)?.as_str().parse::<u32>().ok()),
+                    team: USER_TEAM_PARSER_REGEX.captures(log_line).and_then(|captures| captures.get(1)?.as_str().parse::<u32>().ok())
+                });
+            },
+            LogEvent::ClientBegin => Ok(LogLineEvent::ClientBegin),
+            LogEvent::ClientDisconnect => Ok(LogLineEvent::ClientDisconnect),
+            LogEvent::Item => Ok(LogLineEvent::Item { log_line }),
+            LogEvent::Kill => {
+                let captures = KILL_PARSER_REGEX.captures(log_line).ok_or(LogParserError::RegexParserError)?;
+                let capture = |group: usize| captures.get(group).map_or("", |capture| capture.as_str());
+
+                return Ok(LogLineEvent::Kill {
+                    time_seconds: event_time(capture(1), capture(2)),
+                    killer: capture(3),
+                    victim: capture(4),
+                    weapon: capture(5)
+                });
+            },
+            LogEvent::ShutdownGame => Ok(LogLineEvent::ShutdownGame),
+            LogEvent::Exit => Ok(LogLineEvent::Exit)
+        }
+    }
+
+    pub(crate) fn item_pickup(&self) -> Option<ItemPickup<'a>> {
+
+        let LogLineEvent::Item { log_line } = self else {
+            return None;
+        };
+
+        let captures = ITEM_PARSER_REGEX.captures(log_line)?;
+        let capture = |group: usize| captures.get(group).map_or("", |capture| capture.as_str());
+
+        return Some(ItemPickup {
+            time_seconds: event_time(capture(1), capture(2)),
+            client_id: capture(3).parse::<u32>().ok(),
+            item: capture(4)
+        });
     }
 }
@@ -1,33 +1,75 @@
 use std::future::Future;
 use std::pin::Pin;
 use std::cell::RefCell;
-use std::io::{self, BufRead};
-use std::collections::{HashMap, HashSet};
-
-use crate::interface::{ILogParser, LogParserCallBack, CallbackType, CallbackPayload};
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+
+use crate::interface::{ILogParser, LogParserCallBack, CallbackType, CallbackPayload, ReportRenderer};
 use crate::errors::LogParserError;
-use crate::config::static_config::{STATIC_CONFIG, StaticConfigParameter};
-use crate::config::dynamic_config::{CONFIG, ConfigParameter};
+use crate::config::static_config::{STATIC_CONFIG, StaticConfigParameter, validate_static_config};
+use crate::config::dynamic_config::{CONFIG, Config, ConfigParameter, OrphanEvents, ReportFormat, validate_config};
 use crate::death_causes::DeathCauses;
 use crate::implementation::{
-    death_causes::MatchKillMeans,
     match_data::MatchData,
-    log_event::{
-        LogEvent,
-        KILL_PARSER_REGEX,
-        USER_INFO_PARSER_REGEX
-    },
+    report::report_value,
+    renderers::renderer_factory,
+    log_reader::open_log_file,
+    log_chunks::{LogChunks, LOG_CHUNK_TARGET_BYTES},
+    batch::{ParsedFile, resolve_log_files, merge_matches, worker_count},
+    rating::Ratings,
+    metrics::MetricEvent,
+    log_event::{LogEvent, LogLineEvent},
+    match_event::MatchEvent,
+    interner::NameInterner,
+    death_causes::MatchKillMeans,
+    diagnostics::{DiagnosticKind, MatchInconsistencies, ParseDiagnostic, ParseDiagnostics, ParseMode}
 };
 
-thread_local!(pub static LOG_FILE_PATH: RefCell<Option<String>> = RefCell::new(None) );
+#[cfg(feature = "metrics")]
+use crate::implementation::metrics::Metrics;
+
+thread_local!(pub static LOG_FILE_PATH: RefCell<Option<String>> = const { RefCell::new(None) } );
 
 pub(crate) struct ConcreteLogParser {
-    success_callback: Option<Box<LogParserCallBack>>,
-    warning_callback: Option<Box<LogParserCallBack>>,
-    error_callback: Option<Box<LogParserCallBack>>,
+    success_callback: Option<Arc<LogParserCallBack>>,
+    warning_callback: Option<Arc<LogParserCallBack>>,
+    error_callback: Option<Arc<LogParserCallBack>>,
+    match_callback: Option<Arc<LogParserCallBack>>,
+    match_writer: Option<Box<dyn Write + Send>>,
+    renderer: Option<Arc<dyn ReportRenderer>>,
     matches_data: Vec<MatchData>,
+    matches_count: usize,
     current_match_data: MatchData,
-    first_match: bool
+    first_match: bool,
+    match_open: bool,
+    source_file: Option<String>,
+    line_number: usize,
+    keep_matches: bool,
+    ratings: Option<Ratings>,
+    record_events: bool,
+    names: NameInterner,
+    parse_mode: ParseMode,
+    diagnostics: ParseDiagnostics,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>
+}
+
+/// Matches and diagnostics of one chunk parsed on `parse_file_parallel`.
+type ParsedChunk = (Vec<MatchData>, ParseDiagnostics);
+
+/// Registered handlers, and the parse mode, shared with the parsers of each file on batch parsing.
+#[derive(Clone)]
+struct SharedHandlers {
+    warning_callback: Option<Arc<LogParserCallBack>>,
+    error_callback: Option<Arc<LogParserCallBack>>,
+    parse_mode: ParseMode,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>
 }
 
 impl ConcreteLogParser {
@@ -43,16 +85,83 @@ impl ConcreteLogParser {
             success_callback: None,
             warning_callback: None,
             error_callback: None,
+            match_callback: None,
+            match_writer: None,
+            renderer: None,
             matches_data: Vec::<MatchData>::new(),
-            current_match_data: MatchData {
-                game_match: String::from(""),
-                total_kills: 0,
-                players: HashSet::new(),
-                kills: HashMap::new(),
-                kill_means: if show_death_causes { Some(MatchKillMeans::new()) } else { None } 
-            },
-            first_match: true
+            matches_count: 0,
+            current_match_data: MatchData::new(show_death_causes),
+            first_match: true,
+            match_open: false,
+            source_file: None,
+            line_number: 0,
+            keep_matches: false,
+            ratings: None,
+            record_events: false,
+            names: NameInterner::default(),
+            parse_mode: ParseMode::default(),
+            diagnostics: ParseDiagnostics::default(),
+            #[cfg(feature = "metrics")]
+            metrics: None
+        }
+    }
+
+    fn shared_handlers(&self) -> SharedHandlers {
+        SharedHandlers {
+            warning_callback: self.warning_callback.clone(),
+            error_callback: self.error_callback.clone(),
+            parse_mode: self.parse_mode,
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.clone()
+        }
+    }
+
+    fn file_parser(source_file: String, shared_handlers: SharedHandlers) -> Self {
+
+        let mut file_parser = ConcreteLogParser::new();
+
+        file_parser.warning_callback = shared_handlers.warning_callback;
+        file_parser.error_callback = shared_handlers.error_callback;
+        file_parser.parse_mode = shared_handlers.parse_mode;
+        #[cfg(feature = "metrics")]
+        {
+            file_parser.metrics = shared_handlers.metrics;
         }
+        file_parser.source_file = Some(source_file);
+        file_parser.keep_matches = true;
+
+        return file_parser;
+    }
+
+    /// Parses one chunk of a log split at match boundaries, numbering lines from `first_line`.
+    fn chunk_parser(first_line: usize, shared_handlers: SharedHandlers) -> Self {
+
+        let mut chunk_parser = ConcreteLogParser::new();
+
+        chunk_parser.warning_callback = shared_handlers.warning_callback;
+        chunk_parser.error_callback = shared_handlers.error_callback;
+        chunk_parser.parse_mode = shared_handlers.parse_mode;
+        #[cfg(feature = "metrics")]
+        {
+            chunk_parser.metrics = shared_handlers.metrics;
+        }
+        chunk_parser.line_number = first_line - 1;
+        chunk_parser.keep_matches = true;
+
+        return chunk_parser;
+    }
+
+    /// Keeps every match with its kill and item timeline, as needed to persist them.
+    #[cfg(feature = "sqlite")]
+    pub(crate) fn event_recording_parser(source_file: String) -> Self {
+
+        let mut event_recording_parser = ConcreteLogParser::new();
+
+        event_recording_parser.source_file = Some(source_file);
+        event_recording_parser.keep_matches = true;
+        event_recording_parser.record_events = true;
+
+        return event_recording_parser;
     }
 
     async fn handle_callback(&self, cb_type: CallbackType, error: Option<LogParserError>, data: Option<String>) {
@@ -63,7 +172,7 @@ impl ConcreteLogParser {
 
                     let payload = CallbackPayload {
                         error: None,
-                        data: data
+                        data
                     }; 
         
                     if let Ok(pl) = serde_json::to_value(payload) {
@@ -75,8 +184,8 @@ impl ConcreteLogParser {
                 if let Some(cb) = &self.warning_callback {
 
                     let payload = CallbackPayload {
-                        error: Some(error.unwrap().into()),
-                        data: data
+                        error: error.map(|error| error.into()),
+                        data
                     }; 
         
                     if let Ok(pl) = serde_json::to_value(payload) {
@@ -88,8 +197,21 @@ impl ConcreteLogParser {
                 if let Some(cb) = &self.error_callback {
 
                     let payload = CallbackPayload {
-                        error: Some(error.unwrap().into()),
-                        data: data
+                        error: error.map(|error| error.into()),
+                        data
+                    }; 
+        
+                    if let Ok(pl) = serde_json::to_value(payload) {
+                        let _res = cb(Some(pl)).await;
+                    }
+                }
+            },
+            CallbackType::Match => {
+                if let Some(cb) = &self.match_callback {
+
+                    let payload = CallbackPayload {
+                        error: None,
+                        data
                     }; 
         
                     if let Ok(pl) = serde_json::to_value(payload) {
@@ -101,8 +223,18 @@ impl ConcreteLogParser {
         
     }
 
+    #[cfg(feature = "metrics")]
+    fn record_metric(&self, event: MetricEvent) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record(event);
+        }
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    fn record_metric(&self, _event: MetricEvent) {}
+
     fn get_match_label(&self) -> String {
-        return format!("{}_{}", STATIC_CONFIG.get_parameter(StaticConfigParameter::OutputMatchKey).to_string().as_str(), self.matches_data.len());
+        return format!("{}_{}", STATIC_CONFIG.get_parameter(StaticConfigParameter::OutputMatchKey).to_string().as_str(), self.matches_count);
     }
  
     fn register_new_match_stat(&mut self, match_stats: MatchData) {
@@ -112,133 +244,559 @@ impl ConcreteLogParser {
         );
     }
 
+    async fn stream_match_stat(&mut self, match_stats: MatchData) -> Result<(), LogParserError> {
+
+        let match_line = serde_json::to_string(&match_stats).map_err(|_e| LogParserError::SerializationError)?;
+
+        if let Some(writer) = self.match_writer.as_mut() {
+            writeln!(writer, "{}", match_line).map_err(|_e| LogParserError::WriteError)?;
+            writer.flush().map_err(|_e| LogParserError::WriteError)?;
+        }
+
+        self.handle_callback(
+            CallbackType::Match,
+            None,
+            Some(match_line)
+        ).await;
+
+        return Ok(());
+    }
+
+    fn new_match_data() -> MatchData {
+
+        let mut show_death_causes: bool = false;
+
+        CONFIG.with(|config| {
+            show_death_causes = config.borrow().get_parameter(ConfigParameter::ShowDeathCauses).to_boolean();
+        });
+
+        return MatchData::new(show_death_causes);
+    }
+
+    async fn finish_current_match(&mut self, last_line: usize) -> Result<(), LogParserError> {
+
+        self.match_open = false;
+
+        let mut finished_match = std::mem::replace(&mut self.current_match_data, ConcreteLogParser::new_match_data());
+        finished_match.game_match = self.get_match_label();
+        finished_match.source_file = self.source_file.clone();
+        finished_match.last_line = last_line;
+        self.matches_count += 1;
+
+        self.record_metric(MetricEvent::MatchPlayed);
+
+        return self.publish_match(finished_match).await;
+    }
+
+    async fn publish_match(&mut self, mut finished_match: MatchData) -> Result<(), LogParserError> {
+
+        let mut stream_matches: bool = false;
+
+        CONFIG.with(|config| {
+            stream_matches = config.borrow().get_parameter(ConfigParameter::StreamMatches).to_boolean();
+        });
+
+        self.rate_match(&mut finished_match);
+
+        if !finished_match.inconsistencies.is_empty() {
+            self.diagnostics.inconsistent_matches.push(MatchInconsistencies {
+                game_match: finished_match.game_match.clone(),
+                first_line: finished_match.first_line,
+                last_line: finished_match.last_line,
+                counts: finished_match.inconsistencies.clone()
+            });
+        }
+
+        if stream_matches && !self.keep_matches {
+            return self.stream_match_stat(finished_match).await;
+        }
+
+        self.register_new_match_stat(finished_match);
+
+        return Ok(());
+    }
+
+    fn load_ratings(&mut self) -> Result<(), LogParserError> {
+
+        let mut show_rating_deltas: bool = false;
+        let mut ratings_file_path: Option<String> = None;
+
+        CONFIG.with(|config| {
+            show_rating_deltas = config.borrow().get_parameter(ConfigParameter::ShowRatingDeltas).to_boolean();
+            ratings_file_path = config.borrow().get_parameter(ConfigParameter::RatingsFilePath).to_optional_string();
+        });
+
+        self.ratings = match ratings_file_path {
+            Some(ratings_file_path) => Some(Ratings::load(&ratings_file_path)?),
+            None if show_rating_deltas => Some(Ratings::default()),
+            None => None
+        };
+
+        return Ok(());
+    }
+
+    fn save_ratings(&self) -> Result<(), LogParserError> {
+
+        let mut ratings_file_path: Option<String> = None;
+
+        CONFIG.with(|config| {
+            ratings_file_path = config.borrow().get_parameter(ConfigParameter::RatingsFilePath).to_optional_string();
+        });
+
+        if let (Some(ratings), Some(ratings_file_path)) = (&self.ratings, ratings_file_path) {
+            ratings.save(&ratings_file_path)?;
+        }
+
+        return Ok(());
+    }
+
+    fn rate_match(&mut self, match_stats: &mut MatchData) {
+
+        let mut show_rating_deltas: bool = false;
+
+        CONFIG.with(|config| {
+            show_rating_deltas = config.borrow().get_parameter(ConfigParameter::ShowRatingDeltas).to_boolean();
+        });
+
+        if let Some(ratings) = self.ratings.as_mut() {
+            let rating_deltas = ratings.rate_match(match_stats);
+
+            if show_rating_deltas {
+                match_stats.rating_deltas = Some(rating_deltas);
+            }
+        }
+    }
+
+    async fn parse_lines(&mut self, mut reader: Box<dyn BufRead>) -> Result<(), LogParserError> {
+
+        validate_static_config()?;
+
+        let mut line_bytes: Vec<u8> = Vec::new();
+
+        // A single line buffer is reused for the whole log, events only borrow from it.
+        loop {
+
+            line_bytes.clear();
+
+            if reader.read_until(b'\n', &mut line_bytes).map_err(|_e| LogParserError::ReadFileError)? == 0 {
+                break;
+            }
+
+            let line_len = line_bytes.len() - line_bytes.iter().rev().take_while(|byte| **byte == b'\n' || **byte == b'\r').count();
+            line_bytes.truncate(line_len);
+
+            self.line_number += 1;
+            self.record_metric(MetricEvent::LineProcessed);
+
+            let line = match std::str::from_utf8(&line_bytes) {
+                Ok(line) => line,
+                Err(_e) => {
+                    self.report_line(DiagnosticKind::InvalidEncoding, LogParserError::ReadFileError, &String::from_utf8_lossy(&line_bytes)).await?;
+                    continue;
+                }
+            };
+            
+            match self.parse_log_line(line).await {
+                Err(err @ (LogParserError::WriteError | LogParserError::MalformedLineError { .. })) => return Err(err),
+                Err(err) => {
+                    self.record_metric(MetricEvent::ParseWarning);
+                    self.handle_callback(
+                        CallbackType::Warning,
+                        Some(err), 
+                        Some(String::from(line))
+                    ).await;
+                },
+                Ok(()) => {}
+            }
+        }
+
+        if self.match_open {
+            self.finish_current_match(self.line_number).await?;
+        }
+
+        return Ok(());
+    }
+
+    async fn render_report(&self, parsed_data: &Value) -> Result<String, LogParserError> {
+
+        let mut report_format: ReportFormat = ReportFormat::default();
+
+        CONFIG.with(|config| {
+            report_format = ReportFormat::from_str(config.borrow().get_parameter(ConfigParameter::ReportFormat).to_string().as_str()).unwrap_or_default();
+        });
+
+        let stringfied_json = match &self.renderer {
+            Some(renderer) => renderer.render(parsed_data)?,
+            None => renderer_factory(report_format).render(parsed_data)?
+        };
+
+        self.handle_callback(
+            CallbackType::Success,
+            None,
+            Some(stringfied_json.clone())
+         ).await;
+
+        return Ok(stringfied_json);
+    }
+
+    pub(crate) async fn parse_log_file(&mut self, log_file: &str) -> Result<ParsedFile, LogParserError> {
+
+        let reader = open_log_file(log_file)?;
+
+        self.parse_lines(reader).await?;
+
+        return Ok(ParsedFile {
+            log_file: String::from(log_file),
+            matches: std::mem::take(&mut self.matches_data),
+            diagnostics: std::mem::take(&mut self.diagnostics)
+        });
+    }
+
+    async fn parse_log_files_sequentially(&self, log_files: Vec<PathBuf>) -> Vec<ParsedFile> {
+
+        let mut parsed_files: Vec<ParsedFile> = Vec::new();
+
+        for log_file in log_files {
+
+            let log_file = log_file.to_string_lossy().to_string();
+            let mut file_parser = ConcreteLogParser::file_parser(log_file.clone(), self.shared_handlers());
+
+            match file_parser.parse_log_file(&log_file).await {
+                Ok(parsed_file) => parsed_files.push(parsed_file),
+                Err(err) => self.handle_callback(CallbackType::Error, Some(err), Some(log_file)).await
+            }
+        }
+
+        return parsed_files;
+    }
+
+    /// Parses the log files on up to `workers` blocking threads, collecting them in input order.
+    async fn parse_log_files_concurrently(&self, log_files: Vec<PathBuf>, workers: usize) -> Vec<ParsedFile> {
+
+        let workers = worker_count(workers);
+
+        let mut config_snapshot: Option<Config> = None;
+
+        CONFIG.with(|config| {
+            config_snapshot = Some(config.borrow().clone());
+        });
+
+        let mut tasks = VecDeque::new();
+        let mut parsed_files: Vec<ParsedFile> = Vec::new();
+
+        for log_file in log_files {
+
+            if tasks.len() >= workers {
+                if let Some(task) = tasks.pop_front() {
+                    self.collect_parsed_file(task, &mut parsed_files).await;
+                }
+            }
+
+            let log_file = log_file.to_string_lossy().to_string();
+            let config_snapshot = config_snapshot.clone();
+            let shared_handlers = self.shared_handlers();
+
+            let task = tokio::task::spawn_blocking(move || {
+
+                // CONFIG is thread local: blocking pool threads start from the caller's snapshot.
+                if let Some(config_snapshot) = config_snapshot {
+                    config_snapshot.install();
+                }
+
+                let mut file_parser = ConcreteLogParser::file_parser(log_file.clone(), shared_handlers);
+
+                let result = tokio::runtime::Handle::current().block_on(file_parser.parse_log_file(&log_file));
+
+                return (log_file, result);
+            });
+
+            tasks.push_back(task);
+        }
+
+        while let Some(task) = tasks.pop_front() {
+            self.collect_parsed_file(task, &mut parsed_files).await;
+        }
+
+        return parsed_files;
+    }
+
+    async fn collect_parsed_file(&self, task: JoinHandle<(String, Result<ParsedFile, LogParserError>)>, parsed_files: &mut Vec<ParsedFile>) {
+        match task.await {
+            Ok((_log_file, Ok(parsed_file))) => parsed_files.push(parsed_file),
+            Ok((log_file, Err(err))) => self.handle_callback(CallbackType::Error, Some(err), Some(log_file)).await,
+            Err(_e) => self.handle_callback(CallbackType::Error, Some(LogParserError::UnexpectedError), None).await
+        }
+    }
+
+    /// Parses chunks of whole matches on up to `workers` blocking threads while the log is read,
+    /// then publishes their matches in log order, labeled as if the log was parsed sequentially.
+    async fn parse_chunks_parallel(&mut self, reader: Box<dyn BufRead>, workers: usize) -> Result<(), LogParserError> {
+
+        validate_static_config()?;
+
+        let workers = worker_count(workers);
+
+        let mut config_snapshot: Option<Config> = None;
+
+        CONFIG.with(|config| {
+            config_snapshot = Some(config.borrow().clone());
+        });
+
+        let mut log_chunks = LogChunks::new(reader, LOG_CHUNK_TARGET_BYTES);
+        let mut tasks = VecDeque::new();
+
+        while let Some(log_chunk) = log_chunks.next_chunk()? {
+
+            if tasks.len() >= workers {
+                if let Some(task) = tasks.pop_front() {
+                    self.publish_chunk_matches(task).await?;
+                }
+            }
+
+            let config_snapshot = config_snapshot.clone();
+            let shared_handlers = self.shared_handlers();
+
+            tasks.push_back(tokio::task::spawn_blocking(move || {
+
+                // CONFIG is thread local: blocking pool threads start from the caller's snapshot.
+                if let Some(config_snapshot) = config_snapshot {
+                    config_snapshot.install();
+                }
+
+                let mut chunk_parser = ConcreteLogParser::chunk_parser(log_chunk.first_line, shared_handlers);
+
+                return tokio::runtime::Handle::current().block_on(async move {
+                    chunk_parser.parse_lines(log_chunk.reader()).await?;
+                    return Ok((chunk_parser.matches_data, chunk_parser.diagnostics));
+                });
+            }));
+        }
+
+        while let Some(task) = tasks.pop_front() {
+            self.publish_chunk_matches(task).await?;
+        }
+
+        return Ok(());
+    }
+
+    async fn publish_chunk_matches(&mut self, task: JoinHandle<Result<ParsedChunk, LogParserError>>) -> Result<(), LogParserError> {
+
+        let (chunk_matches, chunk_diagnostics) = task.await.map_err(|_e| LogParserError::UnexpectedError)??;
+
+        self.diagnostics.merge_chunk(chunk_diagnostics, self.parse_mode == ParseMode::Collect);
+
+        for mut chunk_match in chunk_matches {
+            chunk_match.game_match = self.get_match_label();
+            chunk_match.source_file = self.source_file.clone();
+            self.matches_count += 1;
+
+            self.publish_match(chunk_match).await?;
+        }
+
+        return Ok(());
+    }
+
+    fn record_diagnostic(&mut self, kind: DiagnosticKind, line: &str) {
+
+        if kind.is_match_inconsistency() {
+            *self.current_match_data.inconsistencies.entry(kind).or_insert(0) += 1;
+        }
+
+        let diagnostic = ParseDiagnostic { line_number: self.line_number, kind, line: String::from(line) };
+        self.diagnostics.record(diagnostic, self.parse_mode == ParseMode::Collect);
+    }
+
+    /// Warns about a line the parser could not use and records it, then fails on it or goes on,
+    /// as the parse mode says.
+    async fn report_line(&mut self, kind: DiagnosticKind, error: LogParserError, line: &str) -> Result<(), LogParserError> {
+
+        self.record_metric(MetricEvent::ParseWarning);
+        self.handle_callback(CallbackType::Warning, Some(error), Some(String::from(line))).await;
+        self.record_diagnostic(kind, line);
+
+        if self.parse_mode == ParseMode::Strict && kind.is_malformed() {
+            return Err(LogParserError::MalformedLineError { line_number: self.line_number });
+        }
+
+        return Ok(());
+    }
+
     async fn parse_log_line(&mut self, line: &str) -> Result<(), LogParserError> {
 
-        match LogEvent::detect_line_log_event(line)? {
+        let log_event = match LogEvent::detect_line_log_event(line) {
+            Ok(log_event) => log_event,
+            Err(err) => return self.report_line(DiagnosticKind::UnknownLine, err, line).await
+        };
 
-            LogEvent::InitMatch => {
-                if self.first_match == false {
+        let line_event = match LogLineEvent::parse(log_event, line) {
+            Err(err) => return self.report_line(DiagnosticKind::MalformedEvent, err, line).await,
+            Ok(line_event) => line_event
+        };
 
-                    let mut show_death_causes: bool = false;
+        if self.first_match && !self.match_open && !matches!(line_event, LogLineEvent::InitMatch { .. }) {
 
-                    CONFIG.with(|config| {
-                        show_death_causes = config.borrow().get_parameter(ConfigParameter::ShowDeathCauses).to_boolean();
-                    });
+            let mut orphan_events: OrphanEvents = OrphanEvents::default();
 
-                    self.current_match_data.game_match = self.get_match_label();
+            CONFIG.with(|config| {
+                orphan_events = OrphanEvents::from_str(config.borrow().get_parameter(ConfigParameter::OrphanEvents).to_string().as_str()).unwrap_or_default();
+            });
 
-                    self.register_new_match_stat(
-                        self.current_match_data.clone()
-                    );
-    
-                    self.current_match_data = MatchData {
-                        game_match: String::from(""),
-                        total_kills: 0,
-                        players: HashSet::new(),
-                        kills: HashMap::new(),
-                        kill_means: if show_death_causes { Some(MatchKillMeans::new()) } else { None }
-                    };
+            match orphan_events {
+                OrphanEvents::Discard => {
+                    self.record_diagnostic(DiagnosticKind::OrphanedEvent, line);
+                    return Ok(());
+                },
+                // Only the events before the first ShutdownGame make up the partial match.
+                OrphanEvents::PartialMatch if self.matches_count == 0 => {
+                    self.current_match_data.partial = true;
+                    self.current_match_data.first_line = self.line_number;
+                    self.match_open = true;
+                },
+                OrphanEvents::PartialMatch => {}
+            }
+        }
+
+        match line_event {
 
+            LogLineEvent::InitMatch { game_type } => {
+                // A match never shut down ends where the next one starts. Otherwise, the lines since
+                // the last ShutdownGame belong to no match.
+                if self.match_open {
+                    self.finish_current_match(self.line_number - 1).await?;
                 } else {
-                    self.first_match = false;
+                    self.current_match_data = ConcreteLogParser::new_match_data();
                 }
 
+                self.first_match = false;
+
+                self.current_match_data.first_line = self.line_number;
+                self.match_open = true;
+
+                self.current_match_data.game_type = game_type;
+
                 return Ok(());
             },
-            LogEvent::ClientConnect => {
+            LogLineEvent::ClientConnect => {
                 return Ok(());
             },
-            LogEvent::ClientBegin => {
+            LogLineEvent::ClientBegin => {
                 return Ok(());
             },
-            LogEvent::ClientUserinfoChanged => {
-                if let Some(captures) = USER_INFO_PARSER_REGEX.captures(line) {
-                    let player = &captures[1];
+            LogLineEvent::ClientUserinfoChanged { player, client_id, team } => {
+                let player = self.names.intern(player);
 
-                    if !self.current_match_data.players.contains(player) {
-                        self.current_match_data.players.insert(String::from(player));
-                    }
+                if !self.current_match_data.players.contains(&player) {
+                    self.current_match_data.players.insert(player.clone());
+                }
 
-                    return Ok(());
-                } else {
+                self.record_metric(MetricEvent::ActivePlayers(self.current_match_data.players.len()));
 
-                    self.handle_callback(
-                        CallbackType::Warning,
-                          Some(LogParserError::RegexParserError),
-                           Some(String::from(line))
-                     ).await;
+                if let Some(client_id) = client_id {
+                    self.current_match_data.clients.insert(client_id, player.clone());
+                }
 
-                    return Err(LogParserError::RegexParserError);
+                if let Some(team) = team {
+                    self.current_match_data.teams.insert(player, team);
                 }
+
+                return Ok(());
             },
-            LogEvent::Item => {
+            LogLineEvent::Item { .. } => {
+                if self.record_events {
+                    if let Some(item_pickup) = line_event.item_pickup() {
+                        let player = item_pickup.client_id.and_then(|client_id| self.current_match_data.clients.get(&client_id).cloned());
+
+                        self.current_match_data.events.push(MatchEvent::Item {
+                            line: self.line_number,
+                            time_seconds: item_pickup.time_seconds,
+                            player,
+                            item: self.names.intern(item_pickup.item)
+                        });
+                    }
+                }
+
                 return Ok(());
             },
-            LogEvent::Kill => {
-                if let Some(captures) = KILL_PARSER_REGEX.captures(line) {
+            LogLineEvent::Kill { time_seconds, killer, victim, weapon } => {
 
-                    let mut show_death_causes: bool = false;
-                    let mut self_kill_increases_score: bool = false;
-                    let mut being_killed_decreases_score: bool = false;
+                let mut show_death_causes: bool = false;
+                let mut self_kill_increases_score: bool = false;
+                let mut being_killed_decreases_score: bool = false;
 
-                    CONFIG.with(|config| {
-                        show_death_causes = config.borrow().get_parameter(ConfigParameter::ShowDeathCauses).to_boolean();
-                        self_kill_increases_score = config.borrow().get_parameter(ConfigParameter::KillYourselfIncreasesScore).to_boolean();
-                        being_killed_decreases_score = config.borrow().get_parameter(ConfigParameter::BeingKilledDecreasesScore).to_boolean();
-                    });
+                CONFIG.with(|config| {
+                    show_death_causes = config.borrow().get_parameter(ConfigParameter::ShowDeathCauses).to_boolean();
+                    self_kill_increases_score = config.borrow().get_parameter(ConfigParameter::KillYourselfIncreasesScore).to_boolean();
+                    being_killed_decreases_score = config.borrow().get_parameter(ConfigParameter::BeingKilledDecreasesScore).to_boolean();
+                });
 
-                    let killer = &captures[3];
-                    let player_killed = &captures[4];
-                    let gun = &captures[5];
-                    
-                    self.current_match_data.total_kills += 1;
+                self.current_match_data.total_kills += 1;
+                self.record_metric(MetricEvent::Kill(weapon));
 
-                    if killer == STATIC_CONFIG.get_parameter(StaticConfigParameter::WorldLogPattern).to_string().as_str() {
-                        if let Some(kills) = self.current_match_data.kills.get(player_killed) {
-                            self.current_match_data.kills.insert(String::from(player_killed), kills - 1);
-                        } else {
-                            self.current_match_data.kills.insert(String::from(player_killed), -1);
-                        }
-                    } else {
+                let world_kill = killer == STATIC_CONFIG.get_parameter(StaticConfigParameter::WorldLogPattern).to_string().as_str();
+
+                let killer = if world_kill { None } else { Some(self.names.intern(killer)) };
+                let player_killed = self.names.intern(victim);
+                let gun = self.names.intern(weapon);
 
-                        if killer != player_killed || self_kill_increases_score {
-                            if let Some(kills) = self.current_match_data.kills.get(killer) {
-                                self.current_match_data.kills.insert(String::from(killer), kills + 1);
-                            } else {
-                                self.current_match_data.kills.insert(String::from(killer), 1);
-                            }
+                let players = &self.current_match_data.players;
+
+                if !self.match_open {
+                    self.record_diagnostic(DiagnosticKind::KillOutsideMatch, line);
+                } else if killer.as_ref().is_some_and(|killer| !players.contains(killer)) || !players.contains(&player_killed) {
+                    self.record_diagnostic(DiagnosticKind::KillByUnknownPlayer, line);
+                }
+
+                self.current_match_data.register_player_stats(killer.as_ref(), &player_killed, &gun);
+
+                if self.record_events {
+                    self.current_match_data.events.push(MatchEvent::Kill {
+                        line: self.line_number,
+                        time_seconds,
+                        killer: killer.clone(),
+                        victim: player_killed.clone(),
+                        weapon: gun.clone()
+                    });
+                }
+
+                match &killer {
+                    None => {
+                        self.current_match_data.add_score(&player_killed, -1);
+                    },
+                    Some(killer) => {
+
+                        if *killer != player_killed || self_kill_increases_score {
+                            self.current_match_data.add_score(killer, 1);
                         }
 
                         if being_killed_decreases_score {
-                            if let Some(kills) = self.current_match_data.kills.get(player_killed) {
-                                self.current_match_data.kills.insert(String::from(player_killed), kills - 1);
-                            } else {
-                                self.current_match_data.kills.insert(String::from(player_killed), -1);
-                            }
+                            self.current_match_data.add_score(&player_killed, -1);
                         }
                     }
+                }
 
-                    if show_death_causes {
-                        
-                        if let Ok(death_cause) = DeathCauses::from_str(gun) {
-                            self.current_match_data.kill_means.as_mut().unwrap().increase_stat(death_cause);
-                        } else {
-                            return Err(LogParserError::RegexParserError);
-                        }
+                if show_death_causes {
+                    
+                    if let Ok(death_cause) = DeathCauses::from_str(&gun) {
+                        self.current_match_data.kill_means.get_or_insert_with(MatchKillMeans::new).increase_stat(death_cause);
+                    } else {
+                        return self.report_line(DiagnosticKind::UnknownDeathCause, LogParserError::RegexParserError, line).await;
                     }
-
-                    return Ok(());
-                } else {
-                    return Err(LogParserError::RegexParserError);
                 }
+
+                return Ok(());
             },
-            LogEvent::ClientDisconnect => {
+            LogLineEvent::ClientDisconnect => {
                 return Ok(());
             },
-            LogEvent::ShutdownGame => {
+            LogLineEvent::ShutdownGame => {
+                // Streamed right away, a followed log does not wait for the next InitGame.
+                if self.match_open {
+                    self.finish_current_match(self.line_number).await?;
+                }
+
                 return Ok(());
             },
-            LogEvent::Exit => {
+            LogLineEvent::Exit => {
                 return Ok(());
             },
         }
@@ -248,57 +806,172 @@ impl ConcreteLogParser {
 impl ILogParser for ConcreteLogParser {
     
     fn register_success_callback(&mut self, callback: Box<LogParserCallBack>) {
-        self.success_callback = Some(callback);
+        self.success_callback = Some(Arc::from(callback));
     }
 
     fn register_warning_callback(&mut self, callback: Box<LogParserCallBack>) {
-        self.warning_callback = Some(callback);
+        self.warning_callback = Some(Arc::from(callback));
     }
 
     fn register_error_callback(&mut self, callback: Box<LogParserCallBack>) {
-        self.error_callback = Some(callback);
+        self.error_callback = Some(Arc::from(callback));
+    }
+
+    fn register_match_callback(&mut self, callback: Box<LogParserCallBack>) {
+        self.match_callback = Some(Arc::from(callback));
+    }
+
+    fn register_match_writer(&mut self, writer: Box<dyn Write + Send>) {
+        self.match_writer = Some(writer);
+    }
+
+    fn register_renderer(&mut self, renderer: Box<dyn ReportRenderer>) {
+        self.renderer = Some(Arc::from(renderer));
+    }
+
+    #[cfg(feature = "metrics")]
+    fn register_metrics(&mut self, metrics: Arc<Metrics>) {
+        self.metrics = Some(metrics);
+    }
+
+    fn set_parse_mode(&mut self, parse_mode: ParseMode) {
+        self.parse_mode = parse_mode;
+    }
+
+    fn diagnostics(&self) -> &ParseDiagnostics {
+        return &self.diagnostics;
     }
 
     fn parse_file(&mut self) -> Pin<Box<dyn Future<Output = Result<String, LogParserError>> + '_>> {
         let future = async {
 
-            let mut path:String = String::from(""); 
+            validate_config()?;
+
+            let mut log_file_path: Option<String> = None;
 
             CONFIG.with(|config| {
-                if let Some(log_file_path) = config.borrow().get_parameter(ConfigParameter::LogFilePath).to_optional_string() {
-                    path = log_file_path.clone();
-                } else {
-                    panic!("{}", STATIC_CONFIG.get_parameter(StaticConfigParameter::LogFilePathNotFoundErrMsg).to_string().as_str())
-                }
+                log_file_path = config.borrow().get_parameter(ConfigParameter::LogFilePath).to_optional_string();
             });
 
-            let input = std::fs::File::open(path).map_err(|_e| LogParserError::ReadFileError)?;
-            let reader = io::BufReader::new(input);
-        
-            for line in reader.lines() {
-                
-                let line = line.map_err(|_e| LogParserError::ReadFileError)?;
-                
-                if let Err(err) = self.parse_log_line(&line).await {
-                    self.handle_callback(
-                        CallbackType::Warning,
-                        Some(err), 
-                        Some(line)
-                    ).await;
+            let path = log_file_path.ok_or(LogParserError::LogFilePathError)?;
+
+            let reader = open_log_file(&path)?;
+
+            return self.parse_log(reader, Some(path)).await;
+        };
+
+        return Box::pin(future);
+    }
+
+    fn parse_log(&mut self, reader: Box<dyn BufRead>, source: Option<String>) -> Pin<Box<dyn Future<Output = Result<String, LogParserError>> + '_>> {
+        let future = async move {
+
+            validate_config()?;
+
+            let mut report_envelope: bool = false;
+
+            CONFIG.with(|config| {
+                report_envelope = config.borrow().get_parameter(ConfigParameter::ReportEnvelope).to_boolean();
+            });
+
+            // Diagnostics need a place beside the matches, so collecting them envelopes the report.
+            report_envelope |= self.parse_mode == ParseMode::Collect;
+
+            self.load_ratings()?;
+
+            self.parse_lines(reader).await?;
+
+            self.save_ratings()?;
+
+            let parsed_data = report_value(report_envelope, source, &self.matches_data, Some(&self.diagnostics))?;
+
+            return self.render_report(&parsed_data).await;
+        };
+
+        return Box::pin(future);
+    }
+
+    fn parse_file_parallel(&mut self, log_file: String, workers: usize) -> Pin<Box<dyn Future<Output = Result<String, LogParserError>> + '_>> {
+        let future = async move {
+
+            validate_config()?;
+
+            let mut report_envelope: bool = false;
+
+            CONFIG.with(|config| {
+                report_envelope = config.borrow().get_parameter(ConfigParameter::ReportEnvelope).to_boolean();
+            });
+
+            // Diagnostics need a place beside the matches, so collecting them envelopes the report.
+            report_envelope |= self.parse_mode == ParseMode::Collect;
+
+            let reader = open_log_file(&log_file)?;
+
+            self.load_ratings()?;
+
+            self.parse_chunks_parallel(reader, workers).await?;
+
+            self.save_ratings()?;
+
+            let parsed_data = report_value(report_envelope, Some(log_file), &self.matches_data, Some(&self.diagnostics))?;
+
+            return self.render_report(&parsed_data).await;
+        };
+
+        return Box::pin(future);
+    }
+
+    fn parse_files(&mut self, pattern: String, concurrent: bool) -> Pin<Box<dyn Future<Output = Result<String, LogParserError>> + '_>> {
+        let future = async move {
+
+            validate_config()?;
+
+            let mut report_envelope: bool = false;
+
+            CONFIG.with(|config| {
+                report_envelope = config.borrow().get_parameter(ConfigParameter::ReportEnvelope).to_boolean();
+            });
+
+            // Diagnostics need a place beside the matches, so collecting them envelopes the report.
+            report_envelope |= self.parse_mode == ParseMode::Collect;
+
+            let log_files = resolve_log_files(&pattern)?;
+
+            let parsed_files = if concurrent {
+                self.parse_log_files_concurrently(log_files, 0).await
+            } else {
+                self.parse_log_files_sequentially(log_files).await
+            };
+
+            let mut merged_matches = merge_matches(&parsed_files);
+
+            // Line numbers are per file, so only the counts add up across files.
+            for parsed_file in parsed_files.iter() {
+                for (kind, count) in parsed_file.diagnostics.counts.iter() {
+                    *self.diagnostics.counts.entry(*kind).or_insert(0) += count;
                 }
             }
 
-            let parsed_data = serde_json::to_value(&self.matches_data).map_err(|_e| LogParserError::SerializationError)?; 
-            
-            let stringfied_json = serde_json::to_string(&parsed_data).map_err(|_e| LogParserError::StringfyError)?;
+            self.load_ratings()?;
+
+            for merged_match in merged_matches.iter_mut() {
+                self.rate_match(merged_match);
+            }
 
-            self.handle_callback(
-                CallbackType::Success,
-                None,
-                Some(stringfied_json.clone())
-             ).await;
+            self.save_ratings()?;
+
+            let mut files_reports: Vec<Value> = Vec::new();
+
+            for parsed_file in parsed_files.iter() {
+                files_reports.push(report_value(report_envelope, Some(parsed_file.log_file.clone()), &parsed_file.matches, Some(&parsed_file.diagnostics))?);
+            }
+
+            let parsed_data = json!({
+                "merged": report_value(report_envelope, Some(pattern.clone()), &merged_matches, None)?,
+                "files": files_reports
+            });
 
-            return Ok(stringfied_json);
+            return self.render_report(&parsed_data).await;
         };
 
         return Box::pin(future);
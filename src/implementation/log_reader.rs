@@ -0,0 +1,143 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::errors::LogParserError;
+
+const GZIP_MAGIC_BYTES: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC_BYTES: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const BZIP2_MAGIC_BYTES: &[u8] = b"BZh";
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LogCompression {
+    Plain,
+    Gzip,
+    Zstd,
+    Bzip2
+}
+
+impl LogCompression {
+    pub fn detect(path: &Path, header: &[u8]) -> Self {
+
+        if header.starts_with(GZIP_MAGIC_BYTES) {
+            return LogCompression::Gzip;
+        } else if header.starts_with(ZSTD_MAGIC_BYTES) {
+            return LogCompression::Zstd;
+        } else if header.starts_with(BZIP2_MAGIC_BYTES) && matches!(header.get(BZIP2_MAGIC_BYTES.len()), Some(b'1'..=b'9')) {
+            // The magic is followed by the block size digit, a plain log starting with "BZh" is not bzip2.
+            return LogCompression::Bzip2;
+        }
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("gz") => LogCompression::Gzip,
+            Some("zst") | Some("zstd") => LogCompression::Zstd,
+            Some("bz2") => LogCompression::Bzip2,
+            _ => LogCompression::Plain
+        }
+    }
+}
+
+pub fn open_log_file(path: &str) -> Result<Box<dyn BufRead>, LogParserError> {
+
+    let file = File::open(path).map_err(|_e| LogParserError::ReadFileError)?;
+    let mut reader = BufReader::new(file);
+    let header = reader.fill_buf().map_err(|_e| LogParserError::ReadFileError)?;
+
+    let compression = LogCompression::detect(Path::new(path), header);
+
+    return decompress(compression, reader);
+}
+
+pub fn log_bytes_reader(log: Vec<u8>) -> Result<Box<dyn BufRead>, LogParserError> {
+
+    let compression = LogCompression::detect(Path::new(""), &log);
+
+    return decompress(compression, Cursor::new(log));
+}
+
+/// Reads a growing plain text log like `tail -f`: on end of file it waits for new lines
+/// instead of returning EOF, and starts over when the file is truncated by a log rotation.
+pub fn follow_log_file(path: &str, from_start: bool, poll_interval: Duration) -> Result<Box<dyn BufRead>, LogParserError> {
+
+    let mut file = File::open(path).map_err(|_e| LogParserError::ReadFileError)?;
+
+    let position = if from_start {
+        0
+    } else {
+        file.seek(SeekFrom::End(0)).map_err(|_e| LogParserError::ReadFileError)?
+    };
+
+    return Ok(Box::new(BufReader::new(LogFollower { file, position, poll_interval })));
+}
+
+struct LogFollower {
+    file: File,
+    position: u64,
+    poll_interval: Duration
+}
+
+impl Read for LogFollower {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let read = self.file.read(buf)?;
+
+            if read > 0 {
+                self.position += read as u64;
+                return Ok(read);
+            }
+
+            if self.file.metadata()?.len() < self.position {
+                self.position = self.file.seek(SeekFrom::Start(0))?;
+                continue;
+            }
+
+            std::thread::sleep(self.poll_interval);
+        }
+    }
+}
+
+fn decompress<R: BufRead + 'static>(compression: LogCompression, reader: R) -> Result<Box<dyn BufRead>, LogParserError> {
+    match compression {
+        LogCompression::Plain => Ok(Box::new(reader)),
+        LogCompression::Gzip => gzip_reader(reader),
+        LogCompression::Zstd => zstd_reader(reader),
+        LogCompression::Bzip2 => bzip2_reader(reader)
+    }
+}
+
+#[cfg(any(feature = "gzip", feature = "zstd", feature = "bzip2"))]
+fn buffered<R: std::io::Read + 'static>(reader: R) -> Box<dyn BufRead> {
+    return Box::new(BufReader::new(reader));
+}
+
+#[cfg(feature = "gzip")]
+fn gzip_reader<R: BufRead + 'static>(reader: R) -> Result<Box<dyn BufRead>, LogParserError> {
+    return Ok(buffered(flate2::bufread::MultiGzDecoder::new(reader)));
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gzip_reader<R: BufRead + 'static>(_reader: R) -> Result<Box<dyn BufRead>, LogParserError> {
+    return Err(LogParserError::UnsupportedCompressionError);
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_reader<R: BufRead + 'static>(reader: R) -> Result<Box<dyn BufRead>, LogParserError> {
+    let decoder = zstd::stream::read::Decoder::with_buffer(reader).map_err(|_e| LogParserError::ReadFileError)?;
+    return Ok(buffered(decoder));
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_reader<R: BufRead + 'static>(_reader: R) -> Result<Box<dyn BufRead>, LogParserError> {
+    return Err(LogParserError::UnsupportedCompressionError);
+}
+
+#[cfg(feature = "bzip2")]
+fn bzip2_reader<R: BufRead + 'static>(reader: R) -> Result<Box<dyn BufRead>, LogParserError> {
+    return Ok(buffered(bzip2::bufread::MultiBzDecoder::new(reader)));
+}
+
+#[cfg(not(feature = "bzip2"))]
+fn bzip2_reader<R: BufRead + 'static>(_reader: R) -> Result<Box<dyn BufRead>, LogParserError> {
+    return Err(LogParserError::UnsupportedCompressionError);
+}
@@ -1,20 +1,96 @@
-use std::collections::{HashMap, HashSet};
-use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+use serde::{Serialize, Serializer, ser::Error};
 use serde_json::{json};
 
 use crate::config::{
-    dynamic_config::{CONFIG, ConfigParameter},
+    dynamic_config::{CONFIG, ConfigParameter, PlayersOrder},
     static_config::{STATIC_CONFIG, StaticConfigParameter}
 };
-use crate::implementation::death_causes::MatchKillMeans;
+use crate::implementation::{
+    death_causes::MatchKillMeans,
+    player_stats::MatchPlayerStats,
+    match_event::MatchEvent,
+    interner::Name,
+    diagnostics::DiagnosticKind
+};
 
 #[derive(Clone, Default)]
 pub(crate) struct MatchData {
     pub(crate) game_match: String,
     pub(crate) total_kills: i32,
-    pub(crate) players: HashSet<String>,
-    pub(crate) kills: HashMap<String, i32>,
-    pub(crate) kill_means: Option<MatchKillMeans>
+    pub(crate) players: BTreeSet<Name>,
+    pub(crate) kills: BTreeMap<Name, i32>,
+    pub(crate) kill_means: Option<MatchKillMeans>,
+    pub(crate) player_stats: BTreeMap<Name, MatchPlayerStats>,
+    pub(crate) head_to_head: BTreeMap<(Name, Name), usize>,
+    pub(crate) game_type: Option<u32>,
+    pub(crate) teams: BTreeMap<Name, u32>,
+    pub(crate) rating_deltas: Option<BTreeMap<String, f64>>,
+    pub(crate) clients: BTreeMap<u32, Name>,
+    pub(crate) events: Vec<MatchEvent>,
+    pub(crate) source_file: Option<String>,
+    /// Started before the first `InitGame` of the log, so its beginning is missing.
+    pub(crate) partial: bool,
+    /// Lines of the match whose data can not be trusted, reported in the diagnostics section.
+    pub(crate) inconsistencies: BTreeMap<DiagnosticKind, usize>,
+    pub(crate) first_line: usize,
+    pub(crate) last_line: usize
+}
+
+impl MatchData {
+    pub(crate) fn new(show_death_causes: bool) -> Self {
+        Self {
+            game_match: String::from(""),
+            total_kills: 0,
+            players: BTreeSet::new(),
+            kills: BTreeMap::new(),
+            kill_means: if show_death_causes { Some(MatchKillMeans::new()) } else { None },
+            player_stats: BTreeMap::new(),
+            head_to_head: BTreeMap::new(),
+            game_type: None,
+            teams: BTreeMap::new(),
+            rating_deltas: None,
+            clients: BTreeMap::new(),
+            events: Vec::new(),
+            source_file: None,
+            partial: false,
+            inconsistencies: BTreeMap::new(),
+            first_line: 0,
+            last_line: 0
+        }
+    }
+
+    pub(crate) fn register_player_stats(&mut self, killer: Option<&Name>, player_killed: &Name, gun: &Name) {
+
+        if let Some(killer) = killer {
+            if killer != player_killed {
+                self.player_stats.entry(killer.clone()).or_default().register_kill(gun);
+                *self.head_to_head.entry((killer.clone(), player_killed.clone())).or_insert(0) += 1;
+            }
+        }
+
+        self.player_stats.entry(player_killed.clone()).or_default().register_death();
+    }
+
+    /// Adds `points` to the player score, starting from zero.
+    pub(crate) fn add_score(&mut self, player: &Name, points: i32) {
+        *self.kills.entry(player.clone()).or_insert(0) += points;
+    }
+
+    pub(crate) fn ordered_players(&self, players_order: PlayersOrder) -> Vec<&Name> {
+        let mut players: Vec<&Name> = self.players.iter().collect();
+
+        if players_order == PlayersOrder::Score {
+            players.sort_by(|a, b| {
+                let a_kills = self.kills.get(*a).unwrap_or(&0);
+                let b_kills = self.kills.get(*b).unwrap_or(&0);
+                b_kills.cmp(a_kills).then_with(|| a.cmp(b))
+            });
+        }
+
+        return players;
+    }
 }
 
 impl Serialize for MatchData {
@@ -24,29 +100,52 @@ impl Serialize for MatchData {
         S: Serializer,
     {
         let mut show_death_causes: bool = false;
+        let mut show_player_stats: bool = false;
+        let mut players_order: PlayersOrder = PlayersOrder::default();
 
         CONFIG.with(|config| {
             show_death_causes = config.borrow().get_parameter(ConfigParameter::ShowDeathCauses).to_boolean();
+            show_player_stats = config.borrow().get_parameter(ConfigParameter::ShowPlayerStats).to_boolean();
+            players_order = PlayersOrder::from_str(config.borrow().get_parameter(ConfigParameter::PlayersOrder).to_string().as_str()).unwrap_or_default();
+        });
+
+        let players = serde_json::to_value(self.ordered_players(players_order)).map_err(S::Error::custom)?;
+        let kills = serde_json::to_value(&self.kills).map_err(S::Error::custom)?;
+
+        let mut match_stats = json!({
+            STATIC_CONFIG.get_parameter(StaticConfigParameter::TotalKillsKey).to_string().as_str(): &self.total_kills,
+            STATIC_CONFIG.get_parameter(StaticConfigParameter::PlayersKey).to_string().as_str(): players,
+            STATIC_CONFIG.get_parameter(StaticConfigParameter::KillsKey).to_string().as_str(): kills
         });
 
-        if show_death_causes { 
-
-            return json!({
-                &self.game_match: {
-                    STATIC_CONFIG.get_parameter(StaticConfigParameter::TotalKillsKey).to_string().as_str(): &self.total_kills,
-                    STATIC_CONFIG.get_parameter(StaticConfigParameter::PlayersKey).to_string().as_str(): serde_json::to_value(&self.players).unwrap(),
-                    STATIC_CONFIG.get_parameter(StaticConfigParameter::KillsKey).to_string().as_str(): serde_json::to_value(&self.kills).unwrap(),
-                    STATIC_CONFIG.get_parameter(StaticConfigParameter::KillByMeansKey).to_string().as_str(): &self.kill_means.as_ref().unwrap().to_json()
-                }
-            }).serialize(serializer); 
-        } else { 
-            return json!({
-                &self.game_match: {
-                    STATIC_CONFIG.get_parameter(StaticConfigParameter::TotalKillsKey).to_string().as_str(): &self.total_kills,
-                    STATIC_CONFIG.get_parameter(StaticConfigParameter::PlayersKey).to_string().as_str(): serde_json::to_value(&self.players).unwrap(),
-                    STATIC_CONFIG.get_parameter(StaticConfigParameter::KillsKey).to_string().as_str(): serde_json::to_value(&self.kills).unwrap()
-                }
-            }).serialize(serializer);
-        } 
+        if show_death_causes {
+            // A match started before death causes were enabled has no counts yet.
+            match_stats[STATIC_CONFIG.get_parameter(StaticConfigParameter::KillByMeansKey).to_string()] = self.kill_means.as_ref()
+                .map_or_else(|| MatchKillMeans::new().to_json(), |kill_means| kill_means.to_json());
+        }
+
+        if show_player_stats {
+            match_stats[STATIC_CONFIG.get_parameter(StaticConfigParameter::PlayerStatsKey).to_string()] = serde_json::to_value(&self.player_stats).map_err(S::Error::custom)?;
+        }
+
+        if let Some(rating_deltas) = &self.rating_deltas {
+            match_stats[STATIC_CONFIG.get_parameter(StaticConfigParameter::RatingDeltasKey).to_string()] = serde_json::to_value(rating_deltas).map_err(S::Error::custom)?;
+        }
+
+        if self.partial {
+            match_stats[STATIC_CONFIG.get_parameter(StaticConfigParameter::PartialKey).to_string()] = json!(true);
+        }
+
+        if let Some(source_file) = &self.source_file {
+            match_stats[STATIC_CONFIG.get_parameter(StaticConfigParameter::ProvenanceKey).to_string()] = json!({
+                "file": source_file,
+                "first_line": &self.first_line,
+                "last_line": &self.last_line
+            });
+        }
+
+        return json!({
+            &self.game_match: match_stats
+        }).serialize(serializer);
     }
 }
@@ -0,0 +1,24 @@
+use crate::implementation::interner::Name;
+
+/// Match events kept for storage backends, recorded only when requested.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+pub(crate) enum MatchEvent {
+    Kill {
+        line: usize,
+        time_seconds: u32,
+        killer: Option<Name>,
+        victim: Name,
+        weapon: Name
+    },
+    Item {
+        line: usize,
+        time_seconds: u32,
+        player: Option<Name>,
+        item: Name
+    }
+}
+
+pub(crate) fn event_time(minutes: &str, seconds: &str) -> u32 {
+    return minutes.parse::<u32>().unwrap_or(0) * 60 + seconds.parse::<u32>().unwrap_or(0);
+}
@@ -0,0 +1,138 @@
+#[cfg(feature = "metrics")]
+use std::collections::BTreeMap;
+#[cfg(feature = "metrics")]
+use std::fmt::Write;
+#[cfg(feature = "metrics")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "metrics")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "metrics")]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+#[cfg(feature = "metrics")]
+use tokio::net::TcpListener;
+
+#[cfg(feature = "metrics")]
+use crate::errors::LogParserError;
+#[cfg(feature = "metrics")]
+use crate::death_causes::DeathCauses;
+
+#[cfg(feature = "metrics")]
+const UNKNOWN_KILL_MEAN: &str = "unknown";
+#[cfg(feature = "metrics")]
+const MAX_REQUEST_HEAD_BYTES: usize = 8192;
+
+#[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+pub(crate) enum MetricEvent<'a> {
+    LineProcessed,
+    ParseWarning,
+    MatchPlayed,
+    ActivePlayers(usize),
+    Kill(&'a str)
+}
+
+/// Parsing counters and gauges, rendered in Prometheus text exposition format.
+#[cfg(feature = "metrics")]
+#[derive(Default)]
+pub struct Metrics {
+    lines_processed: AtomicU64,
+    parse_warnings: AtomicU64,
+    matches_played: AtomicU64,
+    active_players: AtomicU64,
+    kills_by_means: Mutex<BTreeMap<String, u64>>
+}
+
+#[cfg(feature = "metrics")]
+impl Metrics {
+    pub fn new() -> Self {
+        return Metrics::default();
+    }
+
+    pub(crate) fn record(&self, event: MetricEvent) {
+        match event {
+            MetricEvent::LineProcessed => { self.lines_processed.fetch_add(1, Ordering::Relaxed); },
+            MetricEvent::ParseWarning => { self.parse_warnings.fetch_add(1, Ordering::Relaxed); },
+            MetricEvent::MatchPlayed => {
+                self.matches_played.fetch_add(1, Ordering::Relaxed);
+                self.active_players.store(0, Ordering::Relaxed);
+            },
+            MetricEvent::ActivePlayers(players) => { self.active_players.store(players as u64, Ordering::Relaxed); },
+            MetricEvent::Kill(kill_mean) => {
+                if let Ok(mut kills_by_means) = self.kills_by_means.lock() {
+                    // Labels come from the known death causes, so a forged log cannot grow the series without bound.
+                    let kill_mean = DeathCauses::from_str(kill_mean).map(|cause| cause.to_string()).unwrap_or(String::from(UNKNOWN_KILL_MEAN));
+                    *kills_by_means.entry(kill_mean).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    pub fn render(&self) -> String {
+
+        let mut exposition = String::new();
+
+        let _ = writeln!(exposition, "# HELP quake_log_parser_lines_processed_total Log lines processed.");
+        let _ = writeln!(exposition, "# TYPE quake_log_parser_lines_processed_total counter");
+        let _ = writeln!(exposition, "quake_log_parser_lines_processed_total {}", self.lines_processed.load(Ordering::Relaxed));
+
+        let _ = writeln!(exposition, "# HELP quake_log_parser_parse_warnings_total Log lines that could not be parsed.");
+        let _ = writeln!(exposition, "# TYPE quake_log_parser_parse_warnings_total counter");
+        let _ = writeln!(exposition, "quake_log_parser_parse_warnings_total {}", self.parse_warnings.load(Ordering::Relaxed));
+
+        let _ = writeln!(exposition, "# HELP quake_log_parser_matches_played_total Matches finished.");
+        let _ = writeln!(exposition, "# TYPE quake_log_parser_matches_played_total counter");
+        let _ = writeln!(exposition, "quake_log_parser_matches_played_total {}", self.matches_played.load(Ordering::Relaxed));
+
+        let _ = writeln!(exposition, "# HELP quake_log_parser_active_players Players of the match being parsed.");
+        let _ = writeln!(exposition, "# TYPE quake_log_parser_active_players gauge");
+        let _ = writeln!(exposition, "quake_log_parser_active_players {}", self.active_players.load(Ordering::Relaxed));
+
+        let _ = writeln!(exposition, "# HELP quake_log_parser_kills_total Kills by death cause.");
+        let _ = writeln!(exposition, "# TYPE quake_log_parser_kills_total counter");
+
+        if let Ok(kills_by_means) = self.kills_by_means.lock() {
+            for (kill_mean, kills) in kills_by_means.iter() {
+                let _ = writeln!(exposition, "quake_log_parser_kills_total{{mean=\"{}\"}} {}", kill_mean.replace('\\', "\\\\").replace('"', "\\\""), kills);
+            }
+        }
+
+        return exposition;
+    }
+}
+
+/// Serves `GET /metrics` on the given listener until the task is dropped.
+#[cfg(feature = "metrics")]
+pub async fn serve_metrics(listener: TcpListener, metrics: Arc<Metrics>) -> Result<(), LogParserError> {
+    loop {
+        let (mut stream, _address) = listener.accept().await.map_err(|_e| LogParserError::UnexpectedError)?;
+        let metrics = metrics.clone();
+
+        tokio::spawn(async move {
+
+            let mut request: Vec<u8> = Vec::new();
+            let mut buffer = [0u8; 1024];
+
+            // The request line may arrive split over several reads, so wait for the whole head.
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") && request.len() < MAX_REQUEST_HEAD_BYTES {
+                match stream.read(&mut buffer).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => request.extend_from_slice(&buffer[..read])
+                }
+            }
+
+            let request = String::from_utf8_lossy(&request);
+            let mut request_line = request.lines().next().unwrap_or("").split(' ');
+            let method = request_line.next().unwrap_or("");
+            let path = request_line.next().unwrap_or("").split('?').next().unwrap_or("");
+
+            let response = if method == "GET" && path == "/metrics" {
+                let body = metrics.render();
+                format!("HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body)
+            } else {
+                String::from("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            };
+
+            let _ = stream.write_all(response.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        });
+    }
+}
@@ -1,4 +1,18 @@
 pub mod log_parser;
 pub mod death_causes;
 pub mod log_event;
-pub mod match_data;
\ No newline at end of file
+pub mod match_data;
+pub mod report;
+pub mod renderers;
+pub mod log_reader;
+pub mod batch;
+pub mod player_stats;
+pub mod leaderboard;
+pub mod rating;
+pub mod metrics;
+pub mod match_event;
+pub mod job;
+pub mod interner;
+pub mod log_chunks;
+pub mod synthetic_log;
+pub mod diagnostics;
@@ -0,0 +1,28 @@
+use std::collections::BTreeMap;
+use serde::Serialize;
+
+use crate::implementation::interner::Name;
+
+#[derive(Clone, Default, Serialize)]
+pub(crate) struct MatchPlayerStats {
+    pub(crate) kills: usize,
+    pub(crate) deaths: usize,
+    pub(crate) best_streak: usize,
+    pub(crate) kill_by_means: BTreeMap<Name, usize>,
+    #[serde(skip)]
+    current_streak: usize
+}
+
+impl MatchPlayerStats {
+    pub(crate) fn register_kill(&mut self, gun: &Name) {
+        self.kills += 1;
+        self.current_streak += 1;
+        self.best_streak = self.best_streak.max(self.current_streak);
+        *self.kill_by_means.entry(gun.clone()).or_insert(0) += 1;
+    }
+
+    pub(crate) fn register_death(&mut self) {
+        self.deaths += 1;
+        self.current_streak = 0;
+    }
+}
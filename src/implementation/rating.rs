@@ -0,0 +1,143 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+
+use crate::errors::LogParserError;
+use crate::implementation::{match_data::MatchData, interner::Name};
+
+pub const INITIAL_RATING: f64 = 1500.0;
+pub const K_FACTOR: f64 = 32.0;
+
+const FREE_TEAM: u32 = 0;
+const SPECTATOR_TEAM: u32 = 3;
+const FIRST_TEAM_GAME_TYPE: u32 = 3;
+
+/// Multi-player Elo ratings, updated match by match.
+///
+/// Free for all matches are rated as a round robin of pairwise duels between players,
+/// team matches (TDM, CTF, ...) as duels between teams rated by their members mean rating.
+/// A duel outcome blends final scores with head-to-head kills when both players met.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ratings {
+    pub ratings: BTreeMap<String, f64>,
+    pub matches_rated: usize
+}
+
+struct Side {
+    players: Vec<Name>,
+    rating: f64,
+    score: i64
+}
+
+impl Ratings {
+    pub fn load(path: &str) -> Result<Self, LogParserError> {
+
+        if !Path::new(path).exists() {
+            return Ok(Ratings::default());
+        }
+
+        let file_content = std::fs::read_to_string(path).map_err(|_e| LogParserError::ReadFileError)?;
+        return serde_json::from_str(&file_content).map_err(|_e| LogParserError::SerializationError);
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), LogParserError> {
+        let file_content = serde_json::to_string_pretty(self).map_err(|_e| LogParserError::StringfyError)?;
+        return std::fs::write(path, file_content).map_err(|_e| LogParserError::WriteError);
+    }
+
+    pub fn rating(&self, player: &str) -> f64 {
+        return *self.ratings.get(player).unwrap_or(&INITIAL_RATING);
+    }
+
+    pub(crate) fn rate_match(&mut self, match_data: &MatchData) -> BTreeMap<String, f64> {
+
+        let sides = self.match_sides(match_data);
+        let mut rating_deltas: BTreeMap<String, f64> = BTreeMap::new();
+
+        if sides.len() < 2 {
+            return rating_deltas;
+        }
+
+        let opponents = (sides.len() - 1) as f64;
+
+        for side in sides.iter() {
+
+            let mut side_delta: f64 = 0.0;
+
+            for opponent in sides.iter().filter(|opponent| opponent.players != side.players) {
+                let expected = 1.0 / (1.0 + 10f64.powf((opponent.rating - side.rating) / 400.0));
+                let actual = Self::duel_outcome(match_data, side, opponent);
+                side_delta += K_FACTOR * (actual - expected) / opponents;
+            }
+
+            let side_delta = (side_delta * 100.0).round() / 100.0;
+
+            for player in side.players.iter() {
+                rating_deltas.insert(player.to_string(), side_delta);
+            }
+        }
+
+        for (player, delta) in rating_deltas.iter() {
+            let rating = self.rating(player) + delta;
+            self.ratings.insert(player.clone(), rating);
+        }
+
+        self.matches_rated += 1;
+
+        return rating_deltas;
+    }
+
+    fn match_sides(&self, match_data: &MatchData) -> Vec<Side> {
+
+        let mut participants: BTreeSet<&Name> = match_data.kills.keys().collect();
+        participants.extend(match_data.player_stats.keys());
+        participants.retain(|player| match_data.teams.get(*player) != Some(&SPECTATOR_TEAM));
+
+        let team_game = match_data.game_type.is_some_and(|game_type| game_type >= FIRST_TEAM_GAME_TYPE);
+        let mut teams: BTreeMap<u32, Vec<Name>> = BTreeMap::new();
+
+        for player in participants.iter() {
+            let team = if team_game { *match_data.teams.get(*player).unwrap_or(&FREE_TEAM) } else { FREE_TEAM };
+            teams.entry(team).or_default().push((*player).clone());
+        }
+
+        let groups: Vec<Vec<Name>> = if team_game && teams.len() >= 2 && !teams.contains_key(&FREE_TEAM) {
+            teams.into_values().collect()
+        } else {
+            participants.into_iter().map(|player| vec![player.clone()]).collect()
+        };
+
+        return groups.into_iter().map(|players| {
+            let rating = players.iter().map(|player| self.rating(player)).sum::<f64>() / players.len() as f64;
+            let score = players.iter().map(|player| *match_data.kills.get(player).unwrap_or(&0) as i64).sum();
+            Side { players, rating, score }
+        }).collect();
+    }
+
+    fn duel_outcome(match_data: &MatchData, side: &Side, opponent: &Side) -> f64 {
+
+        let score_outcome = match side.score.cmp(&opponent.score) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0
+        };
+
+        let mut kills_on_opponent: usize = 0;
+        let mut kills_by_opponent: usize = 0;
+
+        for player in side.players.iter() {
+            for rival in opponent.players.iter() {
+                kills_on_opponent += match_data.head_to_head.get(&(player.clone(), rival.clone())).unwrap_or(&0);
+                kills_by_opponent += match_data.head_to_head.get(&(rival.clone(), player.clone())).unwrap_or(&0);
+            }
+        }
+
+        if kills_on_opponent + kills_by_opponent == 0 {
+            return score_outcome;
+        }
+
+        let head_to_head_outcome = kills_on_opponent as f64 / (kills_on_opponent + kills_by_opponent) as f64;
+
+        return (score_outcome + head_to_head_outcome) / 2.0;
+    }
+}
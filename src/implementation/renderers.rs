@@ -0,0 +1,65 @@
+use serde_json::Value;
+
+use crate::interface::ReportRenderer;
+use crate::errors::LogParserError;
+use crate::config::dynamic_config::ReportFormat;
+
+pub struct JsonRenderer;
+
+pub struct PrettyJsonRenderer;
+
+/// Renders one JSON document per line: each match of the report on its own line.
+/// When the report is wrapped in an envelope, the envelope (without matches) comes first.
+/// A `parse_files` report streams its merged matches, each one naming its source file.
+pub struct NdjsonRenderer;
+
+impl ReportRenderer for JsonRenderer {
+    fn render(&self, report: &Value) -> Result<String, LogParserError> {
+        return serde_json::to_string(report).map_err(|_e| LogParserError::StringfyError);
+    }
+}
+
+impl ReportRenderer for PrettyJsonRenderer {
+    fn render(&self, report: &Value) -> Result<String, LogParserError> {
+        return serde_json::to_string_pretty(report).map_err(|_e| LogParserError::StringfyError);
+    }
+}
+
+impl ReportRenderer for NdjsonRenderer {
+    fn render(&self, report: &Value) -> Result<String, LogParserError> {
+
+        if let (Some(merged_report), Some(_files_reports)) = (report.get("merged"), report.get("files")) {
+            return self.render(merged_report);
+        }
+
+        let mut lines: Vec<String> = Vec::new();
+
+        let matches = match report {
+            Value::Array(matches) => matches.clone(),
+            Value::Object(envelope) => {
+                let mut header = envelope.clone();
+                let matches = match header.remove("matches") {
+                    Some(Value::Array(matches)) => matches,
+                    _ => Vec::new()
+                };
+                lines.push(serde_json::to_string(&header).map_err(|_e| LogParserError::StringfyError)?);
+                matches
+            },
+            _ => return Err(LogParserError::SerializationError)
+        };
+
+        for game_match in matches.iter() {
+            lines.push(serde_json::to_string(game_match).map_err(|_e| LogParserError::StringfyError)?);
+        }
+
+        return Ok(lines.join("\n"));
+    }
+}
+
+pub fn renderer_factory(format: ReportFormat) -> Box<dyn ReportRenderer> {
+    match format {
+        ReportFormat::Json => Box::new(JsonRenderer),
+        ReportFormat::PrettyJson => Box::new(PrettyJsonRenderer),
+        ReportFormat::Ndjson => Box::new(NdjsonRenderer)
+    }
+}
@@ -0,0 +1,51 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config::dynamic_config::CONFIG;
+use crate::errors::LogParserError;
+use crate::implementation::{
+    match_data::MatchData,
+    diagnostics::ParseDiagnostics
+};
+
+pub const REPORT_SCHEMA_VERSION: u32 = 2;
+pub const LIBRARY_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Serialize)]
+pub(crate) struct Report<'a> {
+    schema_version: u32,
+    library_version: &'static str,
+    config: Value,
+    source_file: Option<String>,
+    matches: &'a [MatchData],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diagnostics: Option<&'a ParseDiagnostics>
+}
+
+impl<'a> Report<'a> {
+    pub(crate) fn new(source_file: Option<String>, matches: &'a [MatchData], diagnostics: Option<&'a ParseDiagnostics>) -> Self {
+
+        let mut config_snapshot: Value = Value::Null;
+
+        CONFIG.with(|config| {
+            config_snapshot = serde_json::to_value(&*config.borrow()).unwrap_or(Value::Null);
+        });
+
+        Self {
+            schema_version: REPORT_SCHEMA_VERSION,
+            library_version: LIBRARY_VERSION,
+            config: config_snapshot,
+            source_file,
+            matches,
+            diagnostics
+        }
+    }
+}
+
+pub(crate) fn report_value(report_envelope: bool, source_file: Option<String>, matches: &[MatchData], diagnostics: Option<&ParseDiagnostics>) -> Result<Value, LogParserError> {
+    if report_envelope {
+        return serde_json::to_value(Report::new(source_file, matches, diagnostics)).map_err(|_e| LogParserError::SerializationError);
+    } else {
+        return serde_json::to_value(matches).map_err(|_e| LogParserError::SerializationError);
+    }
+}
@@ -0,0 +1,290 @@
+use std::io::Write;
+
+pub const DEFAULT_WEAPONS: [&str; 10] = [
+    "MOD_SHOTGUN",
+    "MOD_GAUNTLET",
+    "MOD_MACHINEGUN",
+    "MOD_GRENADE_SPLASH",
+    "MOD_ROCKET",
+    "MOD_ROCKET_SPLASH",
+    "MOD_PLASMA",
+    "MOD_RAILGUN",
+    "MOD_LIGHTNING",
+    "MOD_BFG_SPLASH"
+];
+
+const WORLD_CLIENT_ID: usize = 1022;
+const WORLD_WEAPONS: [&str; 3] = ["MOD_TRIGGER_HURT", "MOD_FALLING", "MOD_LAVA"];
+const ITEMS: [&str; 6] = ["weapon_rocketlauncher", "weapon_railgun", "item_armor_body", "item_health_large", "ammo_rockets", "item_quad"];
+const SEPARATOR_LINE: &str = "------------------------------------------------------------";
+const INIT_GAME_SETTINGS: &str = "\\sv_floodProtect\\1\\sv_maxPing\\0\\sv_minPing\\0\\sv_maxRate\\10000\\sv_minRate\\0\\sv_hostname\\Synthetic Server\\g_gametype\\0\\sv_privateClients\\2\\sv_maxclients\\16\\sv_allowDownload\\0\\dmflags\\0\\fraglimit\\20\\timelimit\\15\\g_maxGameClients\\0\\capturelimit\\8\\version\\ioq3 1.36 linux-x86_64 Apr 12 2009\\protocol\\68\\mapname\\q3dm17\\gamename\\baseq3\\g_needpass\\0";
+
+/// Deterministic ioquake3 server log generator, for tests and benchmarks.
+///
+/// The same settings and seed always write the same log, e.g.
+/// `SyntheticLog::new(7).with_matches(100).with_players(12).with_renames(0.05).generate()`.
+#[derive(Debug, Clone)]
+pub struct SyntheticLog {
+    seed: u64,
+    matches: usize,
+    players: usize,
+    kills_per_match: usize,
+    items_per_kill: usize,
+    weapons: Vec<String>,
+    world_kill_rate: f64,
+    rename_rate: f64,
+    disconnect_rate: f64,
+    junk_line_rate: f64
+}
+
+/// What a generated log holds, to check parse results against.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SyntheticLogSummary {
+    pub lines: usize,
+    pub matches: usize,
+    pub kills: usize,
+    pub world_kills: usize,
+    pub renames: usize,
+    pub disconnects: usize,
+    /// Separators, end of match scores and junk lines, none of them is a parsed event.
+    pub unknown_lines: usize
+}
+
+impl Default for SyntheticLog {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            matches: 10,
+            players: 8,
+            kills_per_match: 50,
+            items_per_kill: 3,
+            weapons: DEFAULT_WEAPONS.iter().map(|weapon| String::from(*weapon)).collect(),
+            world_kill_rate: 0.1,
+            rename_rate: 0.0,
+            disconnect_rate: 0.0,
+            junk_line_rate: 0.0
+        }
+    }
+}
+
+impl SyntheticLog {
+    pub fn new(seed: u64) -> Self {
+        return SyntheticLog { seed, ..Default::default() };
+    }
+
+    pub fn with_matches(mut self, matches: usize) -> Self {
+        self.matches = matches;
+        return self;
+    }
+
+    /// Players connected at the start of each match, at least 2.
+    pub fn with_players(mut self, players: usize) -> Self {
+        self.players = players.max(2);
+        return self;
+    }
+
+    pub fn with_kills_per_match(mut self, kills_per_match: usize) -> Self {
+        self.kills_per_match = kills_per_match;
+        return self;
+    }
+
+    /// Item pickup lines written before each kill, they are most lines of real logs.
+    pub fn with_items_per_kill(mut self, items_per_kill: usize) -> Self {
+        self.items_per_kill = items_per_kill;
+        return self;
+    }
+
+    /// `MOD_*` death causes picked for player kills. An empty list keeps the defaults.
+    pub fn with_weapons(mut self, weapons: &[&str]) -> Self {
+        if !weapons.is_empty() {
+            self.weapons = weapons.iter().map(|weapon| String::from(*weapon)).collect();
+        }
+        return self;
+    }
+
+    /// Share of kills made by `<world>`.
+    pub fn with_world_kills(mut self, world_kill_rate: f64) -> Self {
+        self.world_kill_rate = world_kill_rate;
+        return self;
+    }
+
+    /// Chance, before each kill, that a player changes name.
+    pub fn with_renames(mut self, rename_rate: f64) -> Self {
+        self.rename_rate = rename_rate;
+        return self;
+    }
+
+    /// Chance, before each kill, that a player leaves the match. Two players always stay.
+    pub fn with_disconnects(mut self, disconnect_rate: f64) -> Self {
+        self.disconnect_rate = disconnect_rate;
+        return self;
+    }
+
+    /// Chance, after each event line, of a line the parser does not understand (chat, separators, garbage).
+    pub fn with_junk_lines(mut self, junk_line_rate: f64) -> Self {
+        self.junk_line_rate = junk_line_rate;
+        return self;
+    }
+
+    pub fn generate(&self) -> (String, SyntheticLogSummary) {
+
+        let mut log: Vec<u8> = Vec::new();
+        let summary = self.write_to(&mut log).unwrap_or_default();
+
+        return (String::from_utf8_lossy(&log).into_owned(), summary);
+    }
+
+    /// Writes the log line by line, so large logs can be streamed to a file.
+    pub fn write_to(&self, writer: &mut dyn Write) -> std::io::Result<SyntheticLogSummary> {
+
+        let mut log_writer = LogWriter {
+            writer,
+            random: SplitMix64(self.seed),
+            junk_line_rate: self.junk_line_rate,
+            seconds: 0,
+            summary: SyntheticLogSummary::default()
+        };
+
+        for match_index in 0..self.matches {
+            self.write_match(&mut log_writer, match_index)?;
+        }
+
+        return Ok(log_writer.summary);
+    }
+
+    fn write_match(&self, log_writer: &mut LogWriter, match_index: usize) -> std::io::Result<()> {
+
+        log_writer.seconds = 0;
+        log_writer.summary.matches += 1;
+
+        log_writer.write_unknown_line(SEPARATOR_LINE)?;
+        log_writer.write_line(&format!("InitGame: {}", INIT_GAME_SETTINGS))?;
+
+        // Client ids start at 2 as on ioquake3 servers, names are unique across the log.
+        let mut clients: Vec<(usize, String)> = (0..self.players)
+            .map(|player| (player + 2, format!("Player {}-{}", match_index, player)))
+            .collect();
+
+        for (client_id, name) in clients.iter() {
+            log_writer.write_line(&format!("ClientConnect: {}", client_id))?;
+            log_writer.write_line(&user_info_line(*client_id, name))?;
+            log_writer.write_line(&format!("ClientBegin: {}", client_id))?;
+        }
+
+        for kill_index in 0..self.kills_per_match {
+
+            log_writer.seconds += 1 + log_writer.random.below(5) as u32;
+
+            if log_writer.random.chance(self.rename_rate) {
+                let renamed = log_writer.random.below(clients.len());
+                clients[renamed].1 = format!("{} ({})", clients[renamed].1, kill_index);
+                log_writer.write_line(&user_info_line(clients[renamed].0, &clients[renamed].1))?;
+                log_writer.summary.renames += 1;
+            }
+
+            if clients.len() > 2 && log_writer.random.chance(self.disconnect_rate) {
+                let (client_id, _name) = clients.remove(log_writer.random.below(clients.len()));
+                log_writer.write_line(&format!("ClientDisconnect: {}", client_id))?;
+                log_writer.summary.disconnects += 1;
+            }
+
+            for _item in 0..self.items_per_kill {
+                let (client_id, _name) = &clients[log_writer.random.below(clients.len())];
+                let item = ITEMS[log_writer.random.below(ITEMS.len())];
+                log_writer.write_line(&format!("Item: {} {}", client_id, item))?;
+            }
+
+            let victim = log_writer.random.below(clients.len());
+            let (victim_id, victim_name) = &clients[victim];
+
+            let kill_line = if log_writer.random.chance(self.world_kill_rate) {
+                log_writer.summary.world_kills += 1;
+                let weapon = WORLD_WEAPONS[log_writer.random.below(WORLD_WEAPONS.len())];
+                format!("Kill: {} {} 22: <world> killed {} by {}", WORLD_CLIENT_ID, victim_id, victim_name, weapon)
+            } else {
+                let killer = (victim + 1 + log_writer.random.below(clients.len() - 1)) % clients.len();
+                let (killer_id, killer_name) = &clients[killer];
+                let weapon = &self.weapons[log_writer.random.below(self.weapons.len())];
+                format!("Kill: {} {} 7: {} killed {} by {}", killer_id, victim_id, killer_name, victim_name, weapon)
+            };
+
+            log_writer.write_line(&kill_line)?;
+            log_writer.summary.kills += 1;
+        }
+
+        log_writer.write_line("Exit: Fraglimit hit.")?;
+
+        for (client_id, name) in clients.iter() {
+            let score = log_writer.random.below(20);
+            log_writer.write_unknown_line(&format!("score: {}  ping: 0  client: {} {}", score, client_id, name))?;
+        }
+
+        log_writer.write_line("ShutdownGame:")?;
+
+        return Ok(());
+    }
+}
+
+fn user_info_line(client_id: usize, name: &str) -> String {
+    return format!("ClientUserinfoChanged: {} n\\{}\\t\\0\\model\\sarge\\hmodel\\sarge\\g_redteam\\\\g_blueteam\\\\c1\\4\\c2\\5\\hc\\100\\w\\0\\l\\0\\tt\\0\\tl\\0", client_id, name);
+}
+
+struct LogWriter<'a> {
+    writer: &'a mut dyn Write,
+    random: SplitMix64,
+    junk_line_rate: f64,
+    seconds: u32,
+    summary: SyntheticLogSummary
+}
+
+impl LogWriter<'_> {
+    fn write_line(&mut self, event: &str) -> std::io::Result<()> {
+
+        writeln!(self.writer, "{:>3}:{:02} {}", self.seconds / 60, self.seconds % 60, event)?;
+        self.summary.lines += 1;
+
+        if self.random.chance(self.junk_line_rate) {
+            match self.random.below(3) {
+                0 => self.write_unknown_line("say: Player: gg")?,
+                1 => self.write_unknown_line(SEPARATOR_LINE)?,
+                _ => {
+                    writeln!(self.writer, "\u{1b}[0m^7 garbled line")?;
+                    self.summary.lines += 1;
+                    self.summary.unknown_lines += 1;
+                }
+            }
+        }
+
+        return Ok(());
+    }
+
+    fn write_unknown_line(&mut self, line: &str) -> std::io::Result<()> {
+
+        writeln!(self.writer, "{:>3}:{:02} {}", self.seconds / 60, self.seconds % 60, line)?;
+        self.summary.lines += 1;
+        self.summary.unknown_lines += 1;
+
+        return Ok(());
+    }
+}
+
+/// Small seeded generator, so logs do not depend on a random crate version.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        return z ^ (z >> 31);
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        return (self.next() % bound.max(1) as u64) as usize;
+    }
+
+    fn chance(&mut self, rate: f64) -> bool {
+        return rate > 0.0 && ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < rate;
+    }
+}
@@ -1,14 +1,22 @@
 use serde_json::Value;
 use std::future::Future;
 use std::pin::Pin;
+use std::io::{BufRead, Write};
 use serde::{Serialize, Deserialize};
 
 use crate::errors::LogParserError;
+use crate::implementation::job::{ParseJob, ParseResult};
+use crate::implementation::diagnostics::{ParseDiagnostics, ParseMode};
+#[cfg(feature = "metrics")]
+use crate::implementation::metrics::Metrics;
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
 
 pub enum CallbackType {
     Success,
     Warning,
-    Error
+    Error,
+    Match
 }
 
 #[derive(Serialize, Deserialize)]
@@ -19,9 +27,31 @@ pub struct CallbackPayload {
 
 pub type LogParserCallBack = dyn Fn(Option<Value>) -> Pin<Box<dyn Future<Output = Result<(), LogParserError>> + Send + Sync >> + Send + Sync;
 
+pub trait ReportRenderer: Send + Sync {
+    fn render(&self, report: &Value) -> Result<String, LogParserError>;
+}
+
 pub trait ILogParser {
     fn register_success_callback(&mut self, callback: Box<LogParserCallBack>);
     fn register_warning_callback(&mut self, callback: Box<LogParserCallBack>);
     fn register_error_callback(&mut self, callback: Box<LogParserCallBack>);
+    fn register_match_callback(&mut self, callback: Box<LogParserCallBack>);
+    fn register_match_writer(&mut self, writer: Box<dyn Write + Send>);
+    fn register_renderer(&mut self, renderer: Box<dyn ReportRenderer>);
+    #[cfg(feature = "metrics")]
+    fn register_metrics(&mut self, metrics: Arc<Metrics>);
+    fn set_parse_mode(&mut self, parse_mode: ParseMode);
+    fn diagnostics(&self) -> &ParseDiagnostics;
     fn parse_file(&mut self) -> Pin<Box<dyn Future<Output = Result<String, LogParserError>> + '_>>;
+    fn parse_log(&mut self, reader: Box<dyn BufRead>, source: Option<String>) -> Pin<Box<dyn Future<Output = Result<String, LogParserError>> + '_>>;
+    fn parse_file_parallel(&mut self, log_file: String, workers: usize) -> Pin<Box<dyn Future<Output = Result<String, LogParserError>> + '_>>;
+    fn parse_files(&mut self, pattern: String, concurrent: bool) -> Pin<Box<dyn Future<Output = Result<String, LogParserError>> + '_>>;
+}
+
+/// A source of parse jobs and a sink of their results, e.g. a RabbitMQ or SQS binding.
+pub trait JobQueue {
+    /// Next pending job, or `None` once the queue is closed and drained.
+    fn next_job(&mut self) -> Pin<Box<dyn Future<Output = Result<Option<ParseJob>, LogParserError>> + '_>>;
+    /// Publishes the result of a job taken from this queue, acknowledging it.
+    fn publish_result(&mut self, result: ParseResult) -> Pin<Box<dyn Future<Output = Result<(), LogParserError>> + '_>>;
 }
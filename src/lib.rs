@@ -1,4 +1,5 @@
 #![recursion_limit = "512"]
+#![allow(clippy::needless_return)]
 
 pub mod errors;
 pub mod interface;
@@ -6,6 +7,11 @@ mod death_causes;
 pub mod implementation;
 mod service;
 pub mod config;
+pub mod schema;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "sqlite")]
+pub mod storage;
 
 pub mod lib {
     use super::{
@@ -27,7 +33,10 @@ mod tests {
     use tokio::test;
 
     use super::{
+        schema::{current_report_schema, report_schema},
         service::LogParser,
+        interface::ReportRenderer,
+        errors::LogParserError,
         implementation::log_parser::{ConcreteLogParser},
         config::{
             config::ConfigValue,
@@ -48,12 +57,1415 @@ mod tests {
 
         let concrete_log_parser = ConcreteLogParser::new();
         let mut log_parser_service = LogParser::new(Box::new(concrete_log_parser));
-        if let Ok(value) = log_parser_service.parse_file().await {
-            println!("{}", value);
-        } else {
-            println!("Error Parsing Log File");
+        let report: serde_json::Value = serde_json::from_str(&log_parser_service.parse_file().await.unwrap()).unwrap();
+
+        assert_eq!(report.as_array().unwrap().len(), 21);
+        assert!(report[20]["game_20"]["kills"].is_object());
+    }
+
+    const GOLDEN_DIR: &str = "tests/golden";
+    const BLESS_ENV_VAR: &str = "BLESS";
+
+    /// Fixture name and log file of each golden report set.
+    const GOLDEN_FIXTURES: [(&str, &str); 2] = [
+        ("sample_log", "sample_log.log"),
+        ("synthetic", "tests/fixtures/synthetic.log")
+    ];
+
+    /// Snapshot name of each `kills_rules` flag, in the order of its bit in a flags combination.
+    const GOLDEN_KILLS_RULES: [(ConfigParameter, &str); 4] = [
+        (ConfigParameter::BeingKilledDecreasesScore, "being_killed_decreases_score"),
+        (ConfigParameter::KillYourselfIncreasesScore, "kill_yourself_increases_score"),
+        (ConfigParameter::ShowDeathCauses, "show_death_causes"),
+        (ConfigParameter::ShowPlayerStats, "show_player_stats")
+    ];
+
+    fn blessing() -> bool {
+        return std::env::var(BLESS_ENV_VAR).is_ok_and(|bless| bless == "1");
+    }
+
+    /// Compares `output` with the checked-in snapshot, or rewrites the snapshot when blessing.
+    /// Returns a description of the mismatch, if any.
+    fn check_snapshot(snapshot_path: &std::path::Path, output: &str) -> Option<String> {
+
+        let output = format!("{}\n", output.trim_end());
+
+        if blessing() {
+            std::fs::create_dir_all(snapshot_path.parent().unwrap()).unwrap();
+            std::fs::write(snapshot_path, output).unwrap();
+            return None;
+        }
+
+        return match std::fs::read_to_string(snapshot_path) {
+            Ok(snapshot) if snapshot == output => None,
+            Ok(_snapshot) => Some(format!("{} differs", snapshot_path.display())),
+            Err(_e) => Some(format!("{} is missing", snapshot_path.display()))
+        };
+    }
+
+    /// Parses every fixture under each combination of `kills_rules` flags and compares the
+    /// pretty printed reports with `tests/golden/<fixture>/<flags>.json`.
+    /// Run `BLESS=1 cargo test golden` to update the snapshots after an intended output change.
+    #[test]
+    async fn test_golden_reports() {
+
+        use std::path::Path;
+        use super::implementation::log_reader::open_log_file;
+
+        CONFIG_FILE_PATH.with(|config_file_path_handler| {
+            *config_file_path_handler.borrow_mut() = Some(String::from("config.json"));
+        });
+
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(ConfigParameter::ReportFormat, ConfigValue::Str(String::from("pretty_json")));
+        });
+
+        let mut mismatches: Vec<String> = Vec::new();
+
+        for (fixture, log_file) in GOLDEN_FIXTURES {
+            for flags in 0..(1 << GOLDEN_KILLS_RULES.len()) {
+
+                let mut enabled_rules: Vec<&str> = Vec::new();
+
+                for (bit, (parameter, rule)) in GOLDEN_KILLS_RULES.into_iter().enumerate() {
+
+                    let enabled = flags & (1 << bit) != 0;
+
+                    CONFIG.with(|config| {
+                        config.borrow_mut().set_parameter(parameter, ConfigValue::Bool(enabled));
+                    });
+
+                    if enabled {
+                        enabled_rules.push(rule);
+                    }
+                }
+
+                let snapshot_name = if enabled_rules.is_empty() { String::from("default") } else { enabled_rules.join("+") };
+                let snapshot_path = Path::new(GOLDEN_DIR).join(fixture).join(format!("{}.json", snapshot_name));
+
+                let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+                let report = log_parser_service.parse_log(open_log_file(log_file).unwrap(), Some(String::from(log_file))).await.unwrap();
+
+                mismatches.extend(check_snapshot(&snapshot_path, &report));
+            }
+        }
+
+        assert!(mismatches.is_empty(), "golden reports changed, run `{}=1 cargo test golden` to bless them:\n{}", BLESS_ENV_VAR, mismatches.join("\n"));
+    }
+
+    #[test]
+    async fn test_deterministic_output() {
+
+        CONFIG_FILE_PATH.with(|config_file_path_handler| {
+            *config_file_path_handler.borrow_mut() = Some(String::from("config.json"));
+        });
+
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(ConfigParameter::LogFilePath, ConfigValue::Str(String::from("sample_log.log")));
+            config.borrow_mut().set_parameter(ConfigParameter::PlayersOrder, ConfigValue::Str(String::from("score")));
+        });
+
+        let mut outputs: Vec<String> = Vec::new();
+
+        for _ in 0..3 {
+            let concrete_log_parser = ConcreteLogParser::new();
+            let mut log_parser_service = LogParser::new(Box::new(concrete_log_parser));
+            outputs.push(log_parser_service.parse_file().await.unwrap());
+        }
+
+        assert!(outputs.windows(2).all(|pair| pair[0] == pair[1]));
+    }
+
+    #[test]
+    async fn test_report_envelope() {
+
+        CONFIG_FILE_PATH.with(|config_file_path_handler| {
+            *config_file_path_handler.borrow_mut() = Some(String::from("config.json"));
+        });
+
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(ConfigParameter::LogFilePath, ConfigValue::Str(String::from("sample_log.log")));
+            config.borrow_mut().set_parameter(ConfigParameter::ReportEnvelope, ConfigValue::Bool(true));
+        });
+
+        let concrete_log_parser = ConcreteLogParser::new();
+        let mut log_parser_service = LogParser::new(Box::new(concrete_log_parser));
+        let report: serde_json::Value = serde_json::from_str(&log_parser_service.parse_file().await.unwrap()).unwrap();
+
+        assert_eq!(report["schema_version"], 2);
+        assert_eq!(report["library_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(report["source_file"], "sample_log.log");
+        assert_eq!(report["config"]["kills_rules"]["show_death_causes"], true);
+        assert!(report["matches"].as_array().is_some_and(|matches| !matches.is_empty()));
+    }
+
+    #[test]
+    async fn test_report_schema_file_is_up_to_date() {
+
+        use super::implementation::report::REPORT_SCHEMA_VERSION;
+
+        let schema_path = std::path::PathBuf::from(format!("schemas/report.v{}.schema.json", REPORT_SCHEMA_VERSION));
+        let schema = serde_json::to_string_pretty(&current_report_schema()).unwrap();
+
+        if let Some(mismatch) = check_snapshot(&schema_path, &schema) {
+            let schema_file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&schema_path).unwrap()).unwrap();
+            assert_eq!(schema_file, current_report_schema(), "{}, run `{}=1 cargo test schema` to bless it", mismatch, BLESS_ENV_VAR);
+        }
+
+        // Published versions are frozen, so they are never blessed.
+        for version in 1..REPORT_SCHEMA_VERSION {
+            let schema_file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(format!("schemas/report.v{}.schema.json", version)).unwrap()).unwrap();
+            assert_eq!(schema_file, report_schema(version).unwrap(), "schemas/report.v{}.schema.json is published and must not change", version);
+        }
+
+        assert_eq!(report_schema(REPORT_SCHEMA_VERSION).unwrap(), current_report_schema());
+        assert!(report_schema(0).is_err());
+        assert!(report_schema(REPORT_SCHEMA_VERSION + 1).is_err());
+    }
+
+    struct MatchCountRenderer;
+
+    impl ReportRenderer for MatchCountRenderer {
+        fn render(&self, report: &serde_json::Value) -> Result<String, LogParserError> {
+            return Ok(report.as_array().map(|matches| matches.len()).unwrap_or(0).to_string());
+        }
+    }
+
+    #[test]
+    async fn test_report_renderers() {
+
+        CONFIG_FILE_PATH.with(|config_file_path_handler| {
+            *config_file_path_handler.borrow_mut() = Some(String::from("config.json"));
+        });
+
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(ConfigParameter::LogFilePath, ConfigValue::Str(String::from("sample_log.log")));
+        });
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        let json_output = log_parser_service.parse_file().await.unwrap();
+        let matches: Vec<serde_json::Value> = serde_json::from_str(&json_output).unwrap();
+
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(ConfigParameter::ReportFormat, ConfigValue::Str(String::from("ndjson")));
+        });
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        let ndjson_output = log_parser_service.parse_file().await.unwrap();
+        let lines: Vec<serde_json::Value> = ndjson_output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines, matches);
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        log_parser_service.register_renderer(Box::new(MatchCountRenderer));
+        assert_eq!(log_parser_service.parse_file().await.unwrap(), matches.len().to_string());
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            return self.0.lock().unwrap().write(buf);
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            return Ok(());
+        }
+    }
+
+    #[test]
+    async fn test_stream_matches() {
+
+        CONFIG_FILE_PATH.with(|config_file_path_handler| {
+            *config_file_path_handler.borrow_mut() = Some(String::from("config.json"));
+        });
+
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(ConfigParameter::LogFilePath, ConfigValue::Str(String::from("sample_log.log")));
+        });
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        let matches: Vec<serde_json::Value> = serde_json::from_str(&log_parser_service.parse_file().await.unwrap()).unwrap();
+        assert_eq!(matches.len(), 21);
+
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(ConfigParameter::StreamMatches, ConfigValue::Bool(true));
+        });
+
+        let buffer = SharedBuffer::default();
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        log_parser_service.register_match_writer(Box::new(buffer.clone()));
+        let report: Vec<serde_json::Value> = serde_json::from_str(&log_parser_service.parse_file().await.unwrap()).unwrap();
+        assert!(report.is_empty());
+
+        let streamed = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let streamed: Vec<serde_json::Value> = streamed.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(streamed, matches);
+    }
+
+    /// Hands out one line per read, noting how many matches were streamed before each read.
+    struct LineByLineReader {
+        lines: std::collections::VecDeque<Vec<u8>>,
+        streamed_matches: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+        streamed_before_read: std::sync::Arc<std::sync::Mutex<Vec<usize>>>
+    }
+
+    impl std::io::Read for LineByLineReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+
+            self.streamed_before_read.lock().unwrap().push(self.streamed_matches.load(std::sync::atomic::Ordering::SeqCst));
+
+            let Some(line) = self.lines.pop_front() else {
+                return Ok(0);
+            };
+
+            buf[..line.len()].copy_from_slice(&line);
+            return Ok(line.len());
+        }
+    }
+
+    #[test]
+    async fn test_match_streamed_on_shutdown() {
+
+        use std::sync::{Arc, Mutex, atomic::{AtomicUsize, Ordering}};
+        use super::config::dynamic_config::Config;
+
+        Config::default().install();
+        CONFIG.with(|config| config.borrow_mut().set_parameter(ConfigParameter::StreamMatches, ConfigValue::Bool(true)));
+
+        let lines = [
+            "  0:00 InitGame: \\g_gametype\\0\\\n",
+            "  0:01 ClientUserinfoChanged: 2 n\\Isgalamido\\t\\0\\\n",
+            "  0:02 Kill: 1022 2 22: <world> killed Isgalamido by MOD_FALLING\n",
+            "  0:03 ShutdownGame:\n",
+            "  0:03 ------------------------------------------------------------\n"
+        ];
+
+        let streamed_matches = Arc::new(AtomicUsize::new(0));
+        let streamed_before_read = Arc::new(Mutex::new(Vec::new()));
+
+        let line_reader = LineByLineReader {
+            lines: lines.iter().map(|line| line.as_bytes().to_vec()).collect(),
+            streamed_matches: streamed_matches.clone(),
+            streamed_before_read: streamed_before_read.clone()
+        };
+
+        let streamed_counter = streamed_matches.clone();
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        log_parser_service.register_match_callback(Box::new(move |_payload| {
+            streamed_counter.fetch_add(1, Ordering::SeqCst);
+            return Box::pin(async { Ok(()) });
+        }));
+
+        log_parser_service.parse_log(Box::new(std::io::BufReader::new(line_reader)), None).await.unwrap();
+
+        // Streamed once the ShutdownGame line is parsed, before the separator line is read.
+        assert_eq!(*streamed_before_read.lock().unwrap(), vec![0, 0, 0, 0, 1, 1]);
+        assert_eq!(streamed_matches.load(Ordering::SeqCst), 1);
+    }
+
+    async fn parse_log_file(log_file_path: &str) -> Result<String, LogParserError> {
+
+        CONFIG_FILE_PATH.with(|config_file_path_handler| {
+            *config_file_path_handler.borrow_mut() = Some(String::from("config.json"));
+        });
+
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(ConfigParameter::LogFilePath, ConfigValue::Str(String::from(log_file_path)));
+        });
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        return log_parser_service.parse_file().await;
+    }
+
+    #[test]
+    async fn test_missing_log_file() {
+        assert!(matches!(parse_log_file("missing_log.log").await, Err(LogParserError::ReadFileError)));
+    }
+
+    #[test]
+    async fn test_job_worker() {
+
+        use super::implementation::job::{InMemoryJobQueue, JobWorker, ParseJob, ParseOutcome, ParseResult};
+
+        let queue = InMemoryJobQueue::new();
+        queue.push_job(ParseJob::new("plain-path", "sample_log.log"));
+        queue.push_job(ParseJob::new("file-uri", "file://sample_log.log").with_config_overrides(serde_json::json!({ "output_rules": { "format": "ndjson" } })));
+        queue.push_job(ParseJob::new("missing", "missing_log.log"));
+        queue.push_job(ParseJob::new("unknown-scheme", "s3://bucket/games.log"));
+
+        let config_before = CONFIG.with(|config| serde_json::to_value(&*config.borrow()).unwrap());
+
+        let mut worker = JobWorker::new(Box::new(queue.clone()));
+        assert_eq!(worker.run().await.unwrap(), 4);
+
+        assert_eq!(CONFIG.with(|config| serde_json::to_value(&*config.borrow()).unwrap()), config_before);
+
+        let results = queue.results();
+        let job_ids: Vec<&str> = results.iter().map(|result| result.job_id.as_str()).collect();
+        assert_eq!(job_ids, vec!["plain-path", "file-uri", "missing", "unknown-scheme"]);
+
+        match &results[0].outcome {
+            ParseOutcome::Succeeded { report } => assert_eq!(serde_json::from_str::<serde_json::Value>(report).unwrap().as_array().unwrap().len(), 21),
+            ParseOutcome::Failed { error } => panic!("{}", error)
+        }
+
+        match &results[1].outcome {
+            ParseOutcome::Succeeded { report } => assert_eq!(report.lines().count(), 21),
+            ParseOutcome::Failed { error } => panic!("{}", error)
+        }
+
+        assert_eq!(results[2].outcome, ParseOutcome::Failed { error: LogParserError::ReadFileError.to_string() });
+        assert_eq!(results[3].outcome, ParseOutcome::Failed { error: LogParserError::UnsupportedSourceError.to_string() });
+
+        let message = serde_json::to_string(&results[2]).unwrap();
+        assert!(message.contains("\"status\":\"failed\""));
+        assert_eq!(serde_json::from_str::<ParseResult>(&message).unwrap(), results[2]);
+
+        let job: ParseJob = serde_json::from_str(r#"{"job_id": "1", "source_uri": "file:///var/log/games.log"}"#).unwrap();
+        assert_eq!(job, ParseJob::new("1", "file:///var/log/games.log"));
+    }
+
+    #[test]
+    async fn test_follow_log_file() {
+
+        use std::io::{BufRead, Write};
+        use std::time::Duration;
+        use super::implementation::{log_event::is_known_log_line, log_reader::follow_log_file};
+
+        let log_file = std::env::temp_dir().join(format!("quake_log_parser_follow_{}.log", std::process::id()));
+        std::fs::write(&log_file, "  0:00 InitGame: \\sv_floodProtect\\1\n").unwrap();
+
+        let mut reader = follow_log_file(log_file.to_str().unwrap(), false, Duration::from_millis(10)).unwrap();
+
+        let appended_line = "  0:05 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT\n";
+        let writer_log_file = log_file.clone();
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            let mut file = std::fs::OpenOptions::new().append(true).open(writer_log_file).unwrap();
+            file.write_all(appended_line.as_bytes()).unwrap();
+        });
+
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        writer.join().unwrap();
+        std::fs::remove_file(&log_file).unwrap();
+
+        assert_eq!(line, appended_line);
+        assert!(is_known_log_line(&line));
+        assert!(!is_known_log_line(" 20:37 ------------------------------------------------------------"));
+    }
+
+    #[test]
+    async fn test_config_loader() {
+
+        use super::config::config_loader::ConfigLoader;
+        use super::errors::ConfigError;
+
+        let env_vars = |vars: &[(&str, &str)]| vars.iter().map(|(name, value)| (String::from(*name), String::from(*value))).collect::<std::collections::BTreeMap<String, String>>();
+
+        let config = ConfigLoader::new()
+            .with_file("config.json")
+            .with_env()
+            .with_env_vars(env_vars(&[("QLP_OUTPUT_RULES__FORMAT", "ndjson"), ("QLP_KILLS_RULES__SHOW_PLAYER_STATS", "true"), ("HOME", "/root")]))
+            .with_overrides(serde_json::json!({ "kills_rules": { "show_player_stats": false } }))
+            .load()
+            .unwrap();
+
+        assert!(config.get_parameter(ConfigParameter::ShowDeathCauses).to_boolean());
+        assert_eq!(config.get_parameter(ConfigParameter::ReportFormat).to_string(), "ndjson");
+        assert!(!config.get_parameter(ConfigParameter::ShowPlayerStats).to_boolean());
+
+        assert_eq!(
+            ConfigLoader::new().with_env().with_env_vars(env_vars(&[("QLP_KILLS_RULES__SHOW_DEATH_CAUSE", "true")])).load().unwrap_err(),
+            ConfigError::UnknownKey { source: String::from("QLP_KILLS_RULES__SHOW_DEATH_CAUSE"), key: String::from("kills_rules.show_death_cause") }
+        );
+
+        assert!(matches!(
+            ConfigLoader::new().with_env_prefix("TEST_QLP_").with_env_vars(env_vars(&[("TEST_QLP_OUTPUT_RULES__PLAYERS_ORDER", "kills")])).load(),
+            Err(ConfigError::InvalidValue { key, .. }) if key == "output_rules.players_order"
+        ));
+
+        // The environment is only read after opting in.
+        assert!(ConfigLoader::new().with_env_vars(env_vars(&[("QLP_KILLS_RULES__SHOW_DEATH_CAUSE", "true")])).load().is_ok());
+
+        assert!(matches!(
+            ConfigLoader::new().with_overrides(serde_json::json!({ "rating_rules": { "show_rating_deltas": "yes" } })).load(),
+            Err(ConfigError::InvalidValue { source, key, .. }) if source == "overrides" && key == "rating_rules.show_rating_deltas"
+        ));
+
+        assert!(matches!(ConfigLoader::new().with_file("sample_log.log").load(), Err(ConfigError::UnsupportedFormat { .. })));
+        assert!(matches!(ConfigLoader::new().with_file("missing_config.json").load(), Err(ConfigError::ReadError { .. })));
+
+        #[cfg(all(feature = "toml", feature = "yaml"))]
+        {
+            let config_dir = std::env::temp_dir().join(format!("quake_log_parser_config_{}", std::process::id()));
+            std::fs::create_dir_all(&config_dir).unwrap();
+
+            let toml_config = config_dir.join("config.toml");
+            std::fs::write(&toml_config, "[output_rules]\nplayers_order = \"score\"\n").unwrap();
+            let config = ConfigLoader::new().with_file(toml_config.to_str().unwrap()).load().unwrap();
+            assert_eq!(config.get_parameter(ConfigParameter::PlayersOrder).to_string(), "score");
+
+            let yaml_config = config_dir.join("config.yaml");
+            std::fs::write(&yaml_config, "kills_rules:\n  being_killed_decreases_score: 1\n").unwrap();
+            assert!(matches!(
+                ConfigLoader::new().with_file(yaml_config.to_str().unwrap()).load(),
+                Err(ConfigError::InvalidValue { key, .. }) if key == "kills_rules.being_killed_decreases_score"
+            ));
+
+            std::fs::remove_dir_all(&config_dir).unwrap();
+        }
+
+        ConfigLoader::new().with_overrides(serde_json::json!({ "output_rules": { "report_envelope": true } })).load().unwrap().install();
+        assert!(CONFIG.with(|config| config.borrow().get_parameter(ConfigParameter::ReportEnvelope).to_boolean()));
+    }
+
+    #[test]
+    async fn test_static_config_validation() {
+
+        use super::config::static_config::{StaticConfig, validate_static_config};
+        use super::errors::{LogParserError, StaticConfigError};
+
+        assert_eq!(validate_static_config(), Ok(()));
+
+        let mut static_config: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("STATIC_CONFIG.json").unwrap()).unwrap();
+        static_config["regex_pattern_engine"]["kill_event"] = serde_json::json!("\\bKill(\\b");
+        static_config["regex_pattern_engine"]["kill_event_line_parser"] = serde_json::json!("(\\d+):(\\d+) Kill: (.*) killed (.*)");
+        static_config["regex_pattern_engine"]["user_info_line_parser"] = serde_json::json!("n\\\\[^\\\\]+\\\\");
+
+        let static_config: StaticConfig = serde_json::from_value(static_config).unwrap();
+        let parser_error = LogParserError::from(static_config.validate_regex_patterns().unwrap_err());
+        assert!(parser_error.to_string().contains("regex_pattern_engine.kill_event = \"\\\\bKill(\\\\b\": "));
+
+        let LogParserError::StaticConfigError(StaticConfigError::InvalidPatterns(invalid_patterns)) = parser_error else {
+            panic!("Expected invalid regex patterns");
+        };
+
+        let keys: Vec<&str> = invalid_patterns.iter().map(|invalid_pattern| invalid_pattern.key.as_str()).collect();
+        assert_eq!(keys, vec!["regex_pattern_engine.kill_event", "regex_pattern_engine.kill_event_line_parser", "regex_pattern_engine.user_info_line_parser"]);
+        assert_eq!(invalid_patterns[1].reason, "expected 5 capture groups, found 4");
+        assert_eq!(invalid_patterns[2].reason, "expected 1 capture groups, found 0");
+    }
+
+    #[test]
+    async fn test_baseline_static_config() {
+
+        use super::config::static_config::{StaticConfig, StaticConfigParameter, STATIC_CONFIG};
+
+        // A STATIC_CONFIG.json written before the keys added since the first release.
+        let static_config: StaticConfig = serde_json::from_str(&std::fs::read_to_string("tests/fixtures/baseline_static_config.json").unwrap()).unwrap();
+        assert_eq!(static_config.validate_regex_patterns(), Ok(()));
+
+        for parameter in [StaticConfigParameter::ProvenanceKey, StaticConfigParameter::PlayerStatsKey, StaticConfigParameter::RatingDeltasKey, StaticConfigParameter::PartialKey,
+            StaticConfigParameter::UserTeamLineParserRegex, StaticConfigParameter::GameTypeLineParserRegex, StaticConfigParameter::ClientIdLineParserRegex, StaticConfigParameter::ItemEventLineParserRegex] {
+            assert_eq!(static_config.get_parameter(parameter).to_string(), STATIC_CONFIG.get_parameter(parameter).to_string());
+        }
+    }
+
+    #[test]
+    async fn test_parse_errors_instead_of_panics() {
+
+        use super::config::dynamic_config::{Config, validate_config};
+        use super::errors::ConfigError;
+        use super::implementation::match_data::MatchData;
+
+        let config_dir = std::env::temp_dir().join(format!("quake_log_parser_bad_config_{}", std::process::id()));
+        std::fs::create_dir_all(&config_dir).unwrap();
+        let bad_config = config_dir.join("config.json");
+        std::fs::write(&bad_config, "{ \"kills_rules\": ").unwrap();
+
+        CONFIG_FILE_PATH.with(|config_file_path_handler| {
+            *config_file_path_handler.borrow_mut() = Some(bad_config.to_string_lossy().to_string());
+        });
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+
+        assert!(matches!(validate_config(), Err(ConfigError::SyntaxError { .. })));
+        assert!(matches!(log_parser_service.parse_log(Box::new(std::io::Cursor::new(Vec::new())), None).await, Err(LogParserError::ConfigError(ConfigError::SyntaxError { .. }))));
+
+        let Err(config_error) = log_parser_service.parse_file().await else {
+            panic!("a bad config file must fail parsing");
+        };
+        assert!(config_error.to_string().starts_with(&format!("An error has happened Loading Config: Invalid config at {}: ", bad_config.to_string_lossy())));
+
+        std::fs::remove_dir_all(&config_dir).unwrap();
+
+        Config::default().install();
+        assert_eq!(validate_config(), Ok(()));
+        assert!(matches!(log_parser_service.parse_file().await, Err(LogParserError::LogFilePathError)));
+
+        // Death causes enabled after the match started.
+        let match_data = MatchData::new(false);
+        CONFIG.with(|config| config.borrow_mut().set_parameter(ConfigParameter::ShowDeathCauses, ConfigValue::Bool(true)));
+        let match_json = serde_json::to_value(&match_data).unwrap();
+        assert_eq!(match_json[""]["kill_by_means"]["MOD_UNKNOWN"], 0);
+    }
+
+    #[test]
+    async fn test_parse_modes() {
+
+        use super::config::dynamic_config::Config;
+        use super::implementation::{
+            diagnostics::{DiagnosticKind, ParseDiagnostics, ParseMode},
+            log_reader::log_bytes_reader
+        };
+
+        Config::default().install();
+        CONFIG.with(|config| config.borrow_mut().set_parameter(ConfigParameter::ShowDeathCauses, ConfigValue::Bool(true)));
+
+        let lines: [&[u8]; 7] = [
+            b"  0:00 InitGame: \\g_gametype\\0\\",
+            b"  0:01 ClientUserinfoChanged: 2 n\\Isgalamido\\t\\0\\",
+            b"  0:02 Kill: 1022 2 22: <world> killed Isgalamido by MOD_UNKNOWN_WEAPON",
+            b"  0:03 Kill: 1022 2",
+            b"  0:04 ------------------------------------------------------------",
+            b"  0:05 say: Isgalamido: \xff\xfe",
+            b"  0:06 ShutdownGame:"
+        ];
+        let log: Vec<u8> = lines.join(&b'\n');
+
+        let parse = |parse_mode: ParseMode, log: Vec<u8>| async move {
+            let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+            log_parser_service.set_parse_mode(parse_mode);
+            return log_parser_service.parse_log(log_bytes_reader(log).unwrap(), None).await;
+        };
+
+        // Lenient parsing skips the undecodable line as any other unusable one.
+        let lenient_report: serde_json::Value = serde_json::from_str(&parse(ParseMode::Lenient, log.clone()).await.unwrap()).unwrap();
+        assert_eq!(lenient_report[0]["game_0"]["total_kills"], 1);
+        assert!(matches!(parse(ParseMode::Strict, log.clone()).await, Err(LogParserError::MalformedLineError { line_number: 3 })));
+
+        let report: serde_json::Value = serde_json::from_str(&parse(ParseMode::Collect, log.clone()).await.unwrap()).unwrap();
+        let diagnostics: ParseDiagnostics = serde_json::from_value(report["diagnostics"].clone()).unwrap();
+
+        let positions: Vec<(usize, DiagnosticKind)> = diagnostics.lines.iter().map(|diagnostic| (diagnostic.line_number, diagnostic.kind)).collect();
+        assert_eq!(positions, vec![
+            (3, DiagnosticKind::UnknownDeathCause),
+            (4, DiagnosticKind::MalformedEvent),
+            (5, DiagnosticKind::UnknownLine),
+            (6, DiagnosticKind::InvalidEncoding)
+        ]);
+        assert_eq!(diagnostics.lines[1].line, "  0:03 Kill: 1022 2");
+        assert_eq!(report["matches"][0]["game_0"]["total_kills"], 1);
+
+        let log_path = std::env::temp_dir().join(format!("quake_log_parser_parse_modes_{}.log", std::process::id()));
+        std::fs::write(&log_path, &log).unwrap();
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        log_parser_service.set_parse_mode(ParseMode::Collect);
+        let parallel_report: serde_json::Value = serde_json::from_str(&log_parser_service.parse_file_parallel(log_path.to_string_lossy().to_string(), 2).await.unwrap()).unwrap();
+        assert_eq!(parallel_report["diagnostics"], report["diagnostics"]);
+
+        std::fs::remove_file(&log_path).unwrap();
+
+        // Unknown lines are no malformed lines.
+        let valid_log: Vec<u8> = [lines[0], lines[1], lines[4], lines[6]].join(&b'\n');
+        assert!(parse(ParseMode::Strict, valid_log).await.is_ok());
+    }
+
+    #[test]
+    async fn test_one_warning_per_bad_line() {
+
+        use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+        use super::config::dynamic_config::Config;
+        use super::implementation::{
+            diagnostics::{DiagnosticKind, ParseDiagnostics},
+            log_reader::log_bytes_reader
+        };
+
+        Config::default().install();
+        CONFIG.with(|config| config.borrow_mut().set_parameter(ConfigParameter::ReportEnvelope, ConfigValue::Bool(true)));
+
+        let mut log = String::from("  0:00 InitGame: \\g_gametype\\0\\\n");
+        log.push_str("  0:01 ClientUserinfoChanged: 2 Isgalamido\n");
+        log.push_str("  0:02 ShutdownGame:\n");
+
+        let warnings = Arc::new(AtomicUsize::new(0));
+        let warnings_counter = warnings.clone();
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        log_parser_service.register_warning_callback(Box::new(move |_payload| {
+            warnings_counter.fetch_add(1, Ordering::SeqCst);
+            return Box::pin(async { Ok(()) });
+        }));
+
+        let report: serde_json::Value = serde_json::from_str(&log_parser_service.parse_log(log_bytes_reader(log.into_bytes()).unwrap(), None).await.unwrap()).unwrap();
+        let diagnostics: ParseDiagnostics = serde_json::from_value(report["diagnostics"].clone()).unwrap();
+
+        assert_eq!(warnings.load(Ordering::SeqCst), 1);
+        assert_eq!(diagnostics.counts.get(&DiagnosticKind::MalformedEvent), Some(&1));
+        assert_eq!(diagnostics.lines.len(), 1);
+    }
+
+    #[test]
+    async fn test_diagnostics_report() {
+
+        use super::config::dynamic_config::Config;
+        use super::implementation::{
+            diagnostics::{DiagnosticKind, ParseDiagnostics, DIAGNOSTIC_LINES_LIMIT},
+            log_reader::log_bytes_reader
+        };
+
+        Config::default().install();
+        CONFIG.with(|config| config.borrow_mut().set_parameter(ConfigParameter::ReportEnvelope, ConfigValue::Bool(true)));
+
+        let mut log = String::from("  0:00 Kill: 1022 2 22: <world> killed Isgalamido by MOD_FALLING\n");
+        log.push_str("  0:01 InitGame: \\g_gametype\\0\\\n");
+        log.push_str("  0:02 ClientUserinfoChanged: 2 n\\Isgalamido\\t\\0\\\n");
+        log.push_str("  0:03 ClientUserinfoChanged: 3 n\\Mocinha\\t\\0\\\n");
+        log.push_str("  0:04 Kill: 3 2 7: Mocinha killed Isgalamido by MOD_ROCKET_SPLASH\n");
+        log.push_str("  0:05 ShutdownGame:\n");
+        log.push_str("  0:06 InitGame: \\g_gametype\\0\\\n");
+        log.push_str("  0:07 ClientUserinfoChanged: 2 n\\Isgalamido\\t\\0\\\n");
+        log.push_str("  0:08 Kill: 4 2 7: Dono da Bola killed Isgalamido by MOD_ROCKET\n");
+        log.push_str("  0:09 ShutdownGame:\n");
+        log.push_str("  0:10 Kill: 2 2 7: Isgalamido killed Isgalamido by MOD_ROCKET\n");
+        for minute in 1..=DIAGNOSTIC_LINES_LIMIT {
+            log.push_str(&format!("{:3}:00 say: Isgalamido: gg\n", minute));
+        }
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        let report: serde_json::Value = serde_json::from_str(&log_parser_service.parse_log(log_bytes_reader(log.into_bytes()).unwrap(), None).await.unwrap()).unwrap();
+        let diagnostics: ParseDiagnostics = serde_json::from_value(report["diagnostics"].clone()).unwrap();
+
+        // The kill before the first InitGame opens a partial match, so only the one after ShutdownGame is outside a match.
+        assert_eq!(diagnostics.counts.get(&DiagnosticKind::KillOutsideMatch), Some(&1));
+        assert_eq!(diagnostics.counts.get(&DiagnosticKind::KillByUnknownPlayer), Some(&2));
+        assert_eq!(diagnostics.counts.get(&DiagnosticKind::UnknownLine), Some(&DIAGNOSTIC_LINES_LIMIT));
+
+        assert_eq!(diagnostics.lines.len(), DIAGNOSTIC_LINES_LIMIT);
+        assert_eq!((diagnostics.lines[0].line_number, diagnostics.lines[0].kind), (1, DiagnosticKind::KillByUnknownPlayer));
+        assert_eq!((diagnostics.lines[2].line_number, diagnostics.lines[2].kind), (11, DiagnosticKind::KillOutsideMatch));
+
+        let inconsistent_matches: Vec<(&str, usize, usize)> = diagnostics.inconsistent_matches.iter()
+            .map(|inconsistent_match| (inconsistent_match.game_match.as_str(), inconsistent_match.first_line, inconsistent_match.counts.values().sum()))
+            .collect();
+        assert_eq!(inconsistent_matches, vec![("game_0", 1, 1), ("game_2", 7, 1)]);
+
+        // Without the envelope the report stays a plain list of matches, and diagnostics are read from the parser.
+        CONFIG.with(|config| config.borrow_mut().set_parameter(ConfigParameter::ReportEnvelope, ConfigValue::Bool(false)));
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        let report: serde_json::Value = serde_json::from_str(&log_parser_service.parse_log(log_bytes_reader(Vec::from("  0:00 junk\n")).unwrap(), None).await.unwrap()).unwrap();
+        assert!(report.is_array());
+        assert_eq!(log_parser_service.diagnostics().counts.get(&DiagnosticKind::UnknownLine), Some(&1));
+        assert_eq!(log_parser_service.diagnostics().lines[0].line, "  0:00 junk");
+    }
+
+    #[test]
+    async fn test_orphaned_events() {
+
+        use super::config::dynamic_config::Config;
+        use super::implementation::{
+            diagnostics::{DiagnosticKind, ParseDiagnostics},
+            log_reader::log_bytes_reader
+        };
+
+        // A rotated log, starting mid-match.
+        let mut log = String::from("  0:00 ClientUserinfoChanged: 2 n\\Isgalamido\\t\\0\\\n");
+        log.push_str("  0:01 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT\n");
+        log.push_str("  0:02 Kill: 1022 2 22: <world> killed Isgalamido by MOD_FALLING\n");
+        log.push_str("  0:03 ShutdownGame:\n");
+        log.push_str("  0:04 InitGame: \\g_gametype\\0\\\n");
+        log.push_str("  0:05 ClientUserinfoChanged: 3 n\\Mocinha\\t\\0\\\n");
+        log.push_str("  0:06 Kill: 1022 3 22: <world> killed Mocinha by MOD_FALLING\n");
+        log.push_str("  0:07 ShutdownGame:\n");
+
+        let parse = |log: String| async move {
+            let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+            let report = log_parser_service.parse_log(log_bytes_reader(log.into_bytes()).unwrap(), None).await.unwrap();
+            return serde_json::from_str::<serde_json::Value>(&report).unwrap();
+        };
+
+        Config::default().install();
+        CONFIG.with(|config| config.borrow_mut().set_parameter(ConfigParameter::ReportEnvelope, ConfigValue::Bool(true)));
+
+        let report = parse(log.clone()).await;
+        let matches = report["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0]["game_0"]["partial"], true);
+        assert_eq!(matches[0]["game_0"]["total_kills"], 2);
+        assert_eq!(matches[0]["game_0"]["players"], serde_json::json!(["Isgalamido"]));
+        assert!(matches[1]["game_1"].get("partial").is_none());
+        assert_eq!(matches[1]["game_1"]["total_kills"], 1);
+
+        let diagnostics: ParseDiagnostics = serde_json::from_value(report["diagnostics"].clone()).unwrap();
+        assert!(diagnostics.counts.is_empty());
+
+        CONFIG.with(|config| config.borrow_mut().set_parameter(ConfigParameter::OrphanEvents, ConfigValue::Str(String::from("discard"))));
+
+        let report = parse(log).await;
+        let matches = report["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0]["game_0"]["total_kills"], 1);
+        assert_eq!(matches[0]["game_0"]["players"], serde_json::json!(["Mocinha"]));
+
+        let diagnostics: ParseDiagnostics = serde_json::from_value(report["diagnostics"].clone()).unwrap();
+        assert_eq!(diagnostics.counts.get(&DiagnosticKind::OrphanedEvent), Some(&4));
+        assert_eq!(diagnostics.lines.iter().map(|diagnostic| diagnostic.line_number).collect::<Vec<usize>>(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    async fn test_log_event_detection() {
+
+        use super::config::static_config::StaticConfig;
+        use super::implementation::log_event::{LogEvent, LogEventDetector};
+
+        assert_eq!(LogEvent::detect_line_log_event(" 21:42 Kill: 1022 2 22: <world> killed Item by MOD_TRIGGER_HURT").ok(), Some(LogEvent::Kill));
+        assert_eq!(LogEvent::detect_line_log_event("  0:00 InitGame: \\sv_floodProtect\\1").ok(), Some(LogEvent::InitMatch));
+        assert_eq!(LogEvent::detect_line_log_event("Item: 2 weapon_rocketlauncher").ok(), Some(LogEvent::Item));
+        assert_eq!(LogEvent::detect_line_log_event("  1:47 ShutdownGame:").ok(), Some(LogEvent::ShutdownGame));
+        assert!(LogEvent::detect_line_log_event("  1:47 score: 20  ping: 4  client: 2 Dono da Bola").is_err());
+        assert!(LogEvent::detect_line_log_event("  0:00 ------------------------------------------------------------").is_err());
+
+        let frag_line = "  1:00 Frag: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT";
+        let kill_line = "  1:00 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT";
+
+        let detector = |kill_event: &str| -> LogEventDetector {
+            let mut static_config: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("STATIC_CONFIG.json").unwrap()).unwrap();
+            static_config["regex_pattern_engine"]["kill_event"] = serde_json::json!(kill_event);
+            let static_config: StaticConfig = serde_json::from_value(static_config).unwrap();
+            return LogEventDetector::new(&static_config);
+        };
+
+        let renamed_kill = detector(r"\bFrag\b");
+        assert_eq!(renamed_kill.detect(frag_line).ok(), Some(LogEvent::Kill));
+        assert!(renamed_kill.detect(kill_line).is_err());
+
+        let kill_or_frag = detector(r"^\s*\d+:\d+ (?:Kill|Frag):");
+        assert_eq!(kill_or_frag.detect(frag_line).ok(), Some(LogEvent::Kill));
+        assert_eq!(kill_or_frag.detect(kill_line).ok(), Some(LogEvent::Kill));
+        assert!(kill_or_frag.detect("  1:00 Kill without a colon").is_err());
+    }
+
+    #[test]
+    async fn test_borrowed_log_line_events() {
+
+        use std::sync::Arc;
+        use super::implementation::{
+            log_event::{LogEvent, LogLineEvent},
+            interner::NameInterner
+        };
+
+        let kill_line = String::from(" 21:42 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT");
+
+        match LogLineEvent::parse(LogEvent::Kill, &kill_line).unwrap() {
+            LogLineEvent::Kill { time_seconds, killer, victim, weapon } => {
+                assert_eq!((time_seconds, killer, victim, weapon), (21 * 60 + 42, "<world>", "Isgalamido", "MOD_TRIGGER_HURT"));
+                assert!(kill_line.as_bytes().as_ptr_range().contains(&victim.as_ptr()));
+            },
+            other => panic!("unexpected event {:?}", other)
+        }
+
+        let user_info_line = r" 20:34 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default";
+        assert_eq!(
+            LogLineEvent::parse(LogEvent::ClientUserinfoChanged, user_info_line).unwrap(),
+            LogLineEvent::ClientUserinfoChanged { player: "Isgalamido", client_id: Some(2), team: Some(0) }
+        );
+
+        let item_event = LogLineEvent::parse(LogEvent::Item, " 20:42 Item: 2 item_armor_body").unwrap();
+        let item_pickup = item_event.item_pickup().unwrap();
+        assert_eq!((item_pickup.time_seconds, item_pickup.client_id, item_pickup.item), (20 * 60 + 42, Some(2), "item_armor_body"));
+
+        assert!(LogLineEvent::parse(LogEvent::Kill, "  1:47 Kill: garbage").is_err());
+
+        let mut names = NameInterner::default();
+        let first = names.intern("Isgalamido");
+        let second = names.intern(&String::from("Isgalamido"));
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(!Arc::ptr_eq(&first, &names.intern("Mocinha")));
+    }
+
+    #[test]
+    async fn test_synthetic_log() {
+
+        use super::implementation::{
+            log_event::is_known_log_line,
+            log_reader::log_bytes_reader,
+            synthetic_log::SyntheticLog
+        };
+
+        CONFIG_FILE_PATH.with(|config_file_path_handler| {
+            *config_file_path_handler.borrow_mut() = Some(String::from("config.json"));
+        });
+
+        let synthetic_log = SyntheticLog::new(42).with_matches(5).with_players(6).with_kills_per_match(80)
+            .with_renames(0.05).with_disconnects(0.02).with_junk_lines(0.01);
+
+        let (log, summary) = synthetic_log.generate();
+
+        assert_eq!(synthetic_log.generate().0, log);
+        assert_ne!(SyntheticLog::new(43).generate().0, SyntheticLog::new(42).generate().0);
+
+        assert_eq!(log.lines().count(), summary.lines);
+        assert_eq!(log.lines().filter(|line| !is_known_log_line(line)).count(), summary.unknown_lines);
+        assert_eq!((summary.matches, summary.kills), (5, 400));
+        assert!(summary.renames > 0 && summary.disconnects > 0 && summary.world_kills > 0);
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        let report = log_parser_service.parse_log(log_bytes_reader(log.into_bytes()).unwrap(), None).await.unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+
+        let matches = report.as_array().unwrap();
+        let total_kills: u64 = matches.iter().map(|game_match| game_match.as_object().unwrap().values().next().unwrap()["total_kills"].as_u64().unwrap()).sum();
+
+        assert_eq!(matches.len(), summary.matches);
+        assert_eq!(total_kills as usize, summary.kills);
+    }
+
+    #[test]
+    async fn test_compression_detection() {
+
+        use std::path::Path;
+        use super::implementation::log_reader::LogCompression;
+
+        assert_eq!(LogCompression::detect(Path::new("games.log"), b"  0:00 InitGame"), LogCompression::Plain);
+        assert_eq!(LogCompression::detect(Path::new("games.log"), &[0x1f, 0x8b, 0x08]), LogCompression::Gzip);
+        assert_eq!(LogCompression::detect(Path::new("games.log"), &[0x28, 0xb5, 0x2f, 0xfd]), LogCompression::Zstd);
+        assert_eq!(LogCompression::detect(Path::new("games.log"), b"BZh9"), LogCompression::Bzip2);
+        assert_eq!(LogCompression::detect(Path::new("games.log"), b"BZh1"), LogCompression::Bzip2);
+        assert_eq!(LogCompression::detect(Path::new("games.log"), b"BZh"), LogCompression::Plain);
+        assert_eq!(LogCompression::detect(Path::new("games.log"), b"BZh0"), LogCompression::Plain);
+        assert_eq!(LogCompression::detect(Path::new("games.log"), b"BZhello world"), LogCompression::Plain);
+        assert_eq!(LogCompression::detect(Path::new("games.log.1.gz"), b""), LogCompression::Gzip);
+        assert_eq!(LogCompression::detect(Path::new("games.log.zst"), b""), LogCompression::Zstd);
+    }
+
+    #[cfg(all(feature = "gzip", feature = "zstd", feature = "bzip2"))]
+    #[test]
+    async fn test_compressed_log_files() {
+
+        use std::io::Write;
+
+        let plain_log = std::fs::read("sample_log.log").unwrap();
+        let expected = parse_log_file("sample_log.log").await.unwrap();
+        let temp_dir = std::env::temp_dir();
+
+        let gzip_path = temp_dir.join("quake_log_parser_lib_sample_log.log.1.gz");
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&plain_log).unwrap();
+        std::fs::write(&gzip_path, encoder.finish().unwrap()).unwrap();
+
+        let zstd_path = temp_dir.join("quake_log_parser_lib_sample_log.log.zst");
+        std::fs::write(&zstd_path, zstd::encode_all(plain_log.as_slice(), 0).unwrap()).unwrap();
+
+        let bzip2_path = temp_dir.join("quake_log_parser_lib_sample_log.log.bz2");
+        let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        encoder.write_all(&plain_log).unwrap();
+        std::fs::write(&bzip2_path, encoder.finish().unwrap()).unwrap();
+
+        for path in [gzip_path, zstd_path, bzip2_path] {
+            assert_eq!(parse_log_file(path.to_str().unwrap()).await.unwrap(), expected);
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    async fn test_parse_files() {
+
+        CONFIG_FILE_PATH.with(|config_file_path_handler| {
+            *config_file_path_handler.borrow_mut() = Some(String::from("config.json"));
+        });
+
+        let logs_dir = std::env::temp_dir().join("quake_log_parser_lib_batch_logs");
+        std::fs::create_dir_all(&logs_dir).unwrap();
+        std::fs::copy("sample_log.log", logs_dir.join("games.log")).unwrap();
+        std::fs::copy("sample_log.log", logs_dir.join("games.log.1")).unwrap();
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        let sequential = log_parser_service.parse_files(logs_dir.to_string_lossy().to_string(), false).await.unwrap();
+        assert_eq!(log_parser_service.diagnostics().counts.get(&super::implementation::diagnostics::DiagnosticKind::UnknownLine), Some(&172));
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        let concurrent = log_parser_service.parse_files(logs_dir.join("games.log*").to_string_lossy().to_string(), true).await.unwrap();
+
+        CONFIG.with(|config| config.borrow_mut().set_parameter(ConfigParameter::ReportFormat, ConfigValue::Str(String::from("ndjson"))));
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        let ndjson_output = log_parser_service.parse_files(logs_dir.to_string_lossy().to_string(), false).await.unwrap();
+        CONFIG.with(|config| config.borrow_mut().set_parameter(ConfigParameter::ReportFormat, ConfigValue::Str(String::from("json"))));
+
+        std::fs::remove_dir_all(&logs_dir).unwrap();
+
+        let sequential: serde_json::Value = serde_json::from_str(&sequential).unwrap();
+        let concurrent: serde_json::Value = serde_json::from_str(&concurrent).unwrap();
+
+        assert_eq!(sequential, concurrent);
+        assert_eq!(sequential["files"].as_array().unwrap().len(), 2);
+        assert_eq!(sequential["merged"].as_array().unwrap().len(), 42);
+
+        let last_match = &sequential["merged"][41]["game_41"];
+        assert_eq!(last_match["source"]["file"], logs_dir.join("games.log.1").to_string_lossy().to_string());
+        assert_eq!(last_match["source"]["last_line"], 5305);
+        assert_eq!(sequential["files"][0][0]["game_0"]["source"]["first_line"], 2);
+
+        // One merged match per line.
+        let ndjson_matches: Vec<serde_json::Value> = ndjson_output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(ndjson_matches.len(), 42);
+        assert_eq!(&ndjson_matches[41], &sequential["merged"][41]);
+    }
+
+    #[test]
+    async fn test_parse_file_parallel() {
+
+        use super::implementation::{
+            log_chunks::LogChunks,
+            log_reader::{open_log_file, log_bytes_reader}
+        };
+
+        CONFIG_FILE_PATH.with(|config_file_path_handler| {
+            *config_file_path_handler.borrow_mut() = Some(String::from("config.json"));
+        });
+
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(ConfigParameter::ShowPlayerStats, ConfigValue::Bool(true));
+        });
+
+        let sample_log = std::fs::read("sample_log.log").unwrap();
+
+        let mut log_chunks = LogChunks::new(log_bytes_reader(sample_log.clone()).unwrap(), 1);
+        let mut chunks_bytes: Vec<u8> = Vec::new();
+        let mut chunks_first_lines: Vec<usize> = Vec::new();
+
+        while let Some(log_chunk) = log_chunks.next_chunk().unwrap() {
+            let chunk = String::from_utf8(log_chunk.bytes.clone()).unwrap();
+            assert_eq!(chunk.matches(" InitGame:").count(), 1);
+            assert!(chunks_first_lines.is_empty() || chunk.lines().next().unwrap().contains(" InitGame:"));
+            chunks_first_lines.push(log_chunk.first_line);
+            chunks_bytes.extend_from_slice(&log_chunk.bytes);
+        }
+
+        assert_eq!(chunks_bytes, sample_log);
+        assert_eq!(chunks_first_lines.len(), 21);
+        assert_eq!(chunks_first_lines[..3], [1, 11, 98]);
+
+        let large_log_path = std::env::temp_dir().join("quake_log_parser_lib_large_log.log");
+        std::fs::write(&large_log_path, sample_log.repeat(20)).unwrap();
+        let large_log_path = large_log_path.to_string_lossy().to_string();
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        let sequential = log_parser_service.parse_log(open_log_file(&large_log_path).unwrap(), Some(large_log_path.clone())).await.unwrap();
+
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        let parallel = log_parser_service.parse_file_parallel(large_log_path.clone(), 4).await.unwrap();
+
+        std::fs::remove_file(&large_log_path).unwrap();
+
+        assert_eq!(sequential, parallel);
+
+        let parallel: serde_json::Value = serde_json::from_str(&parallel).unwrap();
+        assert_eq!(parallel.as_array().unwrap().len(), 21 * 20);
+        assert!(parallel[21 * 20 - 1]["game_419"].is_object());
+    }
+
+    #[test]
+    async fn test_leaderboard() {
+
+        use super::implementation::leaderboard::{Leaderboard, IdentityRules};
+
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(ConfigParameter::ShowPlayerStats, ConfigValue::Bool(true));
+        });
+
+        let report: serde_json::Value = serde_json::from_str(&parse_log_file("sample_log.log").await.unwrap()).unwrap();
+
+        let total_kills: u64 = report.as_array().unwrap().iter()
+            .map(|game_match| game_match.as_object().unwrap().values().next().unwrap()["total_kills"].as_u64().unwrap())
+            .sum();
+
+        let mut leaderboard = Leaderboard::new(IdentityRules::default());
+        leaderboard.fold_report(&report).unwrap();
+
+        let ranking = leaderboard.ranking();
+        assert_eq!(ranking.iter().map(|player| player.deaths as u64).sum::<u64>(), total_kills);
+        assert!(ranking.windows(2).all(|pair| pair[0].wins >= pair[1].wins));
+        assert!(ranking.iter().all(|player| player.best_streak <= player.frags));
+
+        let identity_rules = IdentityRules {
+            case_insensitive: true,
+            strip_color_codes: true,
+            aliases: [(String::from("Dono da Bola"), String::from("Isgalamido"))].into_iter().collect()
+        };
+
+        let mut merged_leaderboard = Leaderboard::new(identity_rules);
+        merged_leaderboard.fold_report(&report).unwrap();
+
+        let isgalamido = merged_leaderboard.ranking().into_iter().find(|player| player.player == "isgalamido").unwrap();
+        assert!(isgalamido.names.contains("Dono da Bola"));
+        assert!(isgalamido.names.contains("Isgalamido"));
+        assert_eq!(merged_leaderboard.ranking().len(), ranking.len() - 1);
+    }
+
+    #[test]
+    async fn test_rating_deltas() {
+
+        use super::implementation::rating::Ratings;
+
+        let ratings_file_path = std::env::temp_dir().join("quake_log_parser_lib_ratings.json").to_string_lossy().to_string();
+        let _ = std::fs::remove_file(&ratings_file_path);
+
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(ConfigParameter::ShowRatingDeltas, ConfigValue::Bool(true));
+            config.borrow_mut().set_parameter(ConfigParameter::RatingsFilePath, ConfigValue::Str(ratings_file_path.clone()));
+        });
+
+        let report: serde_json::Value = serde_json::from_str(&parse_log_file("sample_log.log").await.unwrap()).unwrap();
+
+        for game_match in report.as_array().unwrap() {
+            let rating_deltas = game_match.as_object().unwrap().values().next().unwrap()["rating_deltas"].as_object().unwrap();
+            let deltas: Vec<f64> = rating_deltas.values().map(|delta| delta.as_f64().unwrap()).collect();
+            assert!(deltas.iter().all(|delta| *delta == 0.0) || (deltas.iter().any(|delta| *delta > 0.0) && deltas.iter().any(|delta| *delta < 0.0)));
+        }
+
+        let ratings = Ratings::load(&ratings_file_path).unwrap();
+        assert_eq!(ratings.matches_rated, report.as_array().unwrap().iter().filter(|game_match| {
+            game_match.as_object().unwrap().values().next().unwrap()["rating_deltas"].as_object().unwrap().len() >= 2
+        }).count());
+
+        parse_log_file("sample_log.log").await.unwrap();
+        assert_eq!(Ratings::load(&ratings_file_path).unwrap().matches_rated, 2 * ratings.matches_rated);
+
+        std::fs::remove_file(&ratings_file_path).unwrap();
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    async fn test_metrics() {
+
+        use std::sync::Arc;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use super::implementation::metrics::{Metrics, MetricEvent, serve_metrics};
+
+        CONFIG.with(|config| {
+            config.borrow_mut().set_parameter(ConfigParameter::LogFilePath, ConfigValue::Str(String::from("sample_log.log")));
+        });
+
+        let metrics = Arc::new(Metrics::new());
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+        log_parser_service.register_metrics(metrics.clone());
+        log_parser_service.parse_file().await.unwrap();
+
+        let exposition = metrics.render();
+        assert!(exposition.contains("quake_log_parser_lines_processed_total 5306\n"));
+        assert!(exposition.contains("quake_log_parser_matches_played_total 21\n"));
+        assert!(exposition.contains("quake_log_parser_kills_total{mean=\"MOD_RAILGUN\"}"));
+        assert!(!exposition.contains("quake_log_parser_kills_total{mean=\"unknown\"}"));
+
+        metrics.record(MetricEvent::Kill("MOD_NOT_A_WEAPON"));
+        metrics.record(MetricEvent::Kill("MOD_ANOTHER_FAKE"));
+
+        let exposition = metrics.render();
+        assert!(exposition.contains("quake_log_parser_kills_total{mean=\"unknown\"} 2\n"));
+        assert!(!exposition.contains("MOD_NOT_A_WEAPON"));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve_metrics(listener, metrics.clone()));
+
+        let mut stream = tokio::net::TcpStream::connect(address).await.unwrap();
+        stream.write_all(b"GET /met").await.unwrap();
+        stream.flush().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        stream.write_all(b"rics HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(&exposition));
+
+        let mut stream = tokio::net::TcpStream::connect(address).await.unwrap();
+        stream.write_all(b"GET /metrics-other HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        server.abort();
+
+        assert!(response.starts_with("HTTP/1.1 404 Not Found"));
+    }
+
+    #[cfg(feature = "server")]
+    async fn http_request(address: std::net::SocketAddr, request: Vec<u8>) -> String {
+
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(address).await.unwrap();
+        stream.write_all(&request).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        return response;
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    async fn test_server() {
+
+        use super::server::{ServerState, serve};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve(listener, ServerState::from_current_config()));
+
+        let health = http_request(address, b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n".to_vec()).await;
+        assert!(health.starts_with("HTTP/1.1 200 OK"));
+
+        let log = std::fs::read("sample_log.log").unwrap();
+        let mut upload = format!("POST /reports?format=ndjson HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n", log.len()).into_bytes();
+        upload.extend(log);
+
+        let uploaded = http_request(address, upload).await;
+        assert!(uploaded.starts_with("HTTP/1.1 201 Created"));
+        assert!(uploaded.contains("location: /reports/0"));
+        let uploaded_report = uploaded.split("\r\n\r\n").nth(1).unwrap().to_string();
+        assert_eq!(uploaded_report.lines().count(), 21);
+
+        let cached = http_request(address, b"GET /reports/0 HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n".to_vec()).await;
+        assert_eq!(cached.split("\r\n\r\n").nth(1).unwrap(), uploaded_report);
+
+        let missing = http_request(address, b"GET /reports/1 HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n".to_vec()).await;
+        assert!(missing.starts_with("HTTP/1.1 404 Not Found"));
+
+        server.abort();
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    async fn test_server_upload_limit() {
+
+        use super::server::{ServerState, serve};
+
+        let upload_request = |log: &[u8]| -> Vec<u8> {
+            let mut upload = format!("POST /reports?format=ndjson HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n", log.len()).into_bytes();
+            upload.extend_from_slice(log);
+            return upload;
+        };
+
+        let sample_log = std::fs::read("sample_log.log").unwrap();
+        let copies = 3 * 1024 * 1024 / sample_log.len() + 1;
+        let big_log = sample_log.repeat(copies);
+        assert!(big_log.len() > 3 * 1024 * 1024);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve(listener, ServerState::from_current_config()));
+
+        let uploaded = http_request(address, upload_request(&big_log)).await;
+        assert!(uploaded.starts_with("HTTP/1.1 201 Created"));
+        assert_eq!(uploaded.split("\r\n\r\n").nth(1).unwrap().lines().count(), 21 * copies);
+
+        server.abort();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve(listener, ServerState::from_current_config().with_max_upload_bytes(sample_log.len() - 1)));
+
+        let rejected = http_request(address, upload_request(&sample_log)).await;
+        assert!(rejected.starts_with("HTTP/1.1 413 Payload Too Large"));
+
+        server.abort();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    async fn test_sqlite_store() {
+
+        use super::storage::SqliteStore;
+
+        let mut store = SqliteStore::open_in_memory().unwrap();
+
+        let first_import = store.import_file("sample_log.log").await.unwrap();
+        assert_eq!(first_import.matches, 21);
+        assert!(first_import.kills > 0);
+        assert!(first_import.items > 0);
+
+        let second_import = store.import_file("sample_log.log").await.unwrap();
+        assert_eq!(second_import.log_file_id, first_import.log_file_id);
+        assert_eq!((second_import.matches, second_import.kills, second_import.items), (0, 0, 0));
+
+        let count = |table: &str| -> usize {
+            store.connection().query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get::<_, i64>(0)).unwrap() as usize
+        };
+
+        assert_eq!(count("log_files"), 1);
+        assert_eq!(count("matches"), 21);
+        assert_eq!(count("kills"), first_import.kills);
+        assert_eq!(count("items"), first_import.items);
+
+        let world_kills: i64 = store.connection().query_row("SELECT COUNT(*) FROM kills WHERE killer_id IS NULL", [], |row| row.get(0)).unwrap();
+        assert!(world_kills > 0);
+    }
+
+
+    /// Property tests feeding arbitrary and mutated log lines through event detection and match
+    /// aggregation. Set `PROPTEST_CASES` to run more cases.
+    mod properties {
+
+        use proptest::prelude::*;
+
+        use super::super::{
+            service::LogParser,
+            implementation::{
+                log_parser::ConcreteLogParser,
+                log_event::{LogEvent, LogLineEvent},
+                log_reader::log_bytes_reader
+            },
+            config::{
+                config::ConfigValue,
+                dynamic_config::{CONFIG_FILE_PATH, CONFIG, ConfigParameter}
+            }
+        };
+
+        const TEMPLATE_LOGS: [&str; 2] = ["sample_log.log", "tests/fixtures/synthetic.log"];
+
+        const KILLS_RULES: [ConfigParameter; 4] = [
+            ConfigParameter::BeingKilledDecreasesScore,
+            ConfigParameter::KillYourselfIncreasesScore,
+            ConfigParameter::ShowDeathCauses,
+            ConfigParameter::ShowPlayerStats
+        ];
+
+        #[derive(Debug, Clone)]
+        enum LineMutation {
+            Truncate(usize),
+            Insert(usize, char),
+            Remove(usize),
+            Replace(usize, char)
+        }
+
+        fn template_lines() -> Vec<String> {
+            return TEMPLATE_LOGS.iter()
+                .flat_map(|log_file| std::fs::read_to_string(log_file).unwrap().lines().map(String::from).collect::<Vec<String>>())
+                .collect();
+        }
+
+        fn line_mutation() -> impl Strategy<Value = LineMutation> {
+            // Mostly log syntax characters, so mutations keep reaching the line parsers.
+            let line_char = any::<char>().prop_filter("a line break splits the line", |line_char| *line_char != '\n' && *line_char != '\r');
+            let mutation_char = prop_oneof![prop::sample::select(vec![':', ' ', '\\', '<', '>', '0', '9', 'K', 'n']), line_char];
+
+            return prop_oneof![
+                any::<usize>().prop_map(LineMutation::Truncate),
+                (any::<usize>(), mutation_char.clone()).prop_map(|(index, mutation_char)| LineMutation::Insert(index, mutation_char)),
+                any::<usize>().prop_map(LineMutation::Remove),
+                (any::<usize>(), mutation_char).prop_map(|(index, mutation_char)| LineMutation::Replace(index, mutation_char))
+            ];
+        }
+
+        fn mutate_line(line: &str, mutations: &[LineMutation]) -> String {
+
+            let mut chars: Vec<char> = line.chars().collect();
+
+            for mutation in mutations {
+                let position = |index: &usize, len: usize| index % (len + 1);
+
+                match mutation {
+                    LineMutation::Truncate(index) => chars.truncate(position(index, chars.len())),
+                    LineMutation::Insert(index, mutation_char) => chars.insert(position(index, chars.len()), *mutation_char),
+                    LineMutation::Remove(index) if !chars.is_empty() => { chars.remove(index % chars.len()); },
+                    LineMutation::Replace(index, mutation_char) if !chars.is_empty() => {
+                        let index = index % chars.len();
+                        chars[index] = *mutation_char;
+                    },
+                    _ => {}
+                }
+            }
+
+            return chars.into_iter().collect();
+        }
+
+        /// Template log lines, left untouched half of the time.
+        fn mutated_line(template_lines: Vec<String>) -> impl Strategy<Value = String> {
+            return (prop::sample::select(template_lines), prop::collection::vec(line_mutation(), 0..3), any::<bool>())
+                .prop_map(|(line, mutations, mutate)| if mutate { mutate_line(&line, &mutations) } else { line });
+        }
+
+        /// Matches and kills inside them, as a match opens on InitGame, or on the first event of the
+        /// log as a partial match, and closes on ShutdownGame or the next InitGame.
+        fn expected_matches(lines: &[String]) -> (usize, usize) {
+
+            let (mut matches, mut kills, mut match_open, mut first_match) = (0, 0, false, true);
+
+            let log_events = lines.iter().filter_map(|line| {
+                return LogEvent::detect_line_log_event(line).ok().filter(|log_event| LogLineEvent::parse(*log_event, line).is_ok());
+            });
+
+            for log_event in log_events {
+
+                if first_match && !match_open && matches == 0 && log_event != LogEvent::InitMatch {
+                    match_open = true;
+                }
+
+                match log_event {
+                    LogEvent::InitMatch => {
+                        matches += usize::from(match_open);
+                        match_open = true;
+                        first_match = false;
+                    },
+                    LogEvent::ShutdownGame => {
+                        matches += usize::from(match_open);
+                        match_open = false;
+                    },
+                    LogEvent::Kill => kills += usize::from(match_open),
+                    _ => {}
+                }
+            }
+
+            return (matches + usize::from(match_open), kills);
+        }
+
+        fn parse_report(log: &str, kills_rules: u8) -> serde_json::Value {
+
+            CONFIG_FILE_PATH.with(|config_file_path_handler| {
+                *config_file_path_handler.borrow_mut() = Some(String::from("config.json"));
+            });
+
+            for (bit, parameter) in KILLS_RULES.into_iter().enumerate() {
+                CONFIG.with(|config| {
+                    config.borrow_mut().set_parameter(parameter, ConfigValue::Bool(kills_rules & (1 << bit) != 0));
+                });
+            }
+
+            let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+
+            let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+            log_parser_service.register_warning_callback(Box::new(|_payload| Box::pin(async { Ok(()) })));
+
+            let report = runtime.block_on(log_parser_service.parse_log(log_bytes_reader(log.as_bytes().to_vec()).unwrap(), None)).unwrap();
+
+            return serde_json::from_str(&report).unwrap();
+        }
+
+        proptest! {
+
+            #![proptest_config(ProptestConfig::with_cases(256))]
+
+            #[test]
+            fn arbitrary_lines_are_detected_without_panicking(line in any::<String>()) {
+                if let Ok(log_event) = LogEvent::detect_line_log_event(&line) {
+                    let _line_event = LogLineEvent::parse(log_event, &line);
+                }
+            }
+
+            #[test]
+            fn mutated_logs_count_every_accepted_kill(lines in prop::collection::vec(mutated_line(template_lines()), 1..150), kills_rules in 0u8..16) {
+
+                let log = lines.join("\n");
+                let report = parse_report(&log, kills_rules);
+
+                let matches: Vec<&serde_json::Value> = report.as_array().unwrap().iter()
+                    .map(|game_match| game_match.as_object().unwrap().values().next().unwrap())
+                    .collect();
+
+                let (expected_matches, kills_in_matches) = expected_matches(&lines);
+                let total_kills: u64 = matches.iter().map(|game_match| game_match["total_kills"].as_u64().unwrap()).sum();
+
+                prop_assert_eq!(matches.len(), expected_matches);
+                prop_assert_eq!(total_kills as usize, kills_in_matches);
+
+                for game_match in matches {
+                    let players = game_match["players"].as_array().unwrap();
+                    prop_assert!(players.windows(2).all(|pair| pair[0].as_str() < pair[1].as_str()));
+                }
+            }
         }
-        
     }
 
 }
\ No newline at end of file
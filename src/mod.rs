@@ -3,4 +3,5 @@ pub mod interface;
 pub mod death_causes;
 pub mod implementation;
 pub mod service;
-pub mod config;
\ No newline at end of file
+pub mod config;
+pub mod schema;
\ No newline at end of file
@@ -0,0 +1,278 @@
+use serde_json::{json, Value};
+
+use crate::errors::LogParserError;
+use crate::config::static_config::{STATIC_CONFIG, StaticConfigParameter};
+use crate::implementation::report::REPORT_SCHEMA_VERSION;
+
+pub fn current_report_schema() -> Value {
+    return v2_report_schema();
+}
+
+pub fn report_schema(version: u32) -> Result<Value, LogParserError> {
+    match version {
+        1 => Ok(v1_report_schema()),
+        2 => Ok(v2_report_schema()),
+        _ => Err(LogParserError::SchemaVersionError)
+    }
+}
+
+/// The report schema as first published. Frozen: later report fields go to a new version.
+fn v1_report_schema() -> Value {
+
+    let match_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::OutputMatchKey).to_string();
+    let total_kills_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::TotalKillsKey).to_string();
+    let players_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::PlayersKey).to_string();
+    let kills_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::KillsKey).to_string();
+    let kill_by_means_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::KillByMeansKey).to_string();
+
+    return json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": "quake_log_parser_lib/report.v1.schema.json",
+        "title": "Quake Log Parser Report",
+        "type": "object",
+        "required": ["schema_version", "library_version", "config", "source_file", "matches"],
+        "additionalProperties": false,
+        "properties": {
+            "schema_version": { "const": 1 },
+            "library_version": { "type": "string" },
+            "config": {
+                "type": "object",
+                "required": ["kills_rules", "output_rules"],
+                "properties": {
+                    "kills_rules": {
+                        "type": "object",
+                        "required": ["being_killed_decreases_score", "kill_yourself_increases_score", "show_death_causes"],
+                        "properties": {
+                            "being_killed_decreases_score": { "type": "boolean" },
+                            "kill_yourself_increases_score": { "type": "boolean" },
+                            "show_death_causes": { "type": "boolean" }
+                        }
+                    },
+                    "output_rules": {
+                        "type": "object",
+                        "properties": {
+                            "players_order": { "enum": ["name", "score"] },
+                            "report_envelope": { "type": "boolean" }
+                        }
+                    }
+                }
+            },
+            "source_file": { "type": ["string", "null"] },
+            "matches": {
+                "type": "array",
+                "items": { "$ref": "#/$defs/match" }
+            }
+        },
+        "$defs": {
+            "match": {
+                "type": "object",
+                "minProperties": 1,
+                "maxProperties": 1,
+                "patternProperties": {
+                    format!("^{}_\\d+$", match_key): { "$ref": "#/$defs/match_stats" }
+                },
+                "additionalProperties": false
+            },
+            "match_stats": {
+                "type": "object",
+                "required": [&total_kills_key, &players_key, &kills_key],
+                "additionalProperties": false,
+                "properties": {
+                    total_kills_key.clone(): { "type": "integer", "minimum": 0 },
+                    players_key.clone(): {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "uniqueItems": true
+                    },
+                    kills_key.clone(): {
+                        "type": "object",
+                        "additionalProperties": { "type": "integer" }
+                    },
+                    kill_by_means_key.clone(): {
+                        "type": "object",
+                        "additionalProperties": { "type": "integer", "minimum": 0 }
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// v1 plus match provenance, player stats, rating deltas, partial matches and diagnostics, all of them optional.
+fn v2_report_schema() -> Value {
+
+    let match_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::OutputMatchKey).to_string();
+    let total_kills_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::TotalKillsKey).to_string();
+    let players_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::PlayersKey).to_string();
+    let kills_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::KillsKey).to_string();
+    let kill_by_means_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::KillByMeansKey).to_string();
+    let provenance_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::ProvenanceKey).to_string();
+    let player_stats_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::PlayerStatsKey).to_string();
+    let rating_deltas_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::RatingDeltasKey).to_string();
+    let partial_key = STATIC_CONFIG.get_parameter(StaticConfigParameter::PartialKey).to_string();
+
+    return json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": format!("quake_log_parser_lib/report.v{}.schema.json", REPORT_SCHEMA_VERSION),
+        "title": "Quake Log Parser Report",
+        "type": "object",
+        "required": ["schema_version", "library_version", "config", "source_file", "matches"],
+        "additionalProperties": false,
+        "properties": {
+            "schema_version": { "const": REPORT_SCHEMA_VERSION },
+            "library_version": { "type": "string" },
+            "config": {
+                "type": "object",
+                "required": ["kills_rules", "output_rules"],
+                "properties": {
+                    "kills_rules": {
+                        "type": "object",
+                        "required": ["being_killed_decreases_score", "kill_yourself_increases_score", "show_death_causes"],
+                        "properties": {
+                            "being_killed_decreases_score": { "type": "boolean" },
+                            "kill_yourself_increases_score": { "type": "boolean" },
+                            "show_death_causes": { "type": "boolean" },
+                            "show_player_stats": { "type": "boolean" }
+                        }
+                    },
+                    "parse_rules": {
+                        "type": "object",
+                        "properties": {
+                            "orphan_events": { "enum": ["partial_match", "discard"] }
+                        }
+                    },
+                    "rating_rules": {
+                        "type": "object",
+                        "properties": {
+                            "show_rating_deltas": { "type": "boolean" },
+                            "ratings_file_path": { "type": ["string", "null"] }
+                        }
+                    },
+                    "output_rules": {
+                        "type": "object",
+                        "properties": {
+                            "players_order": { "enum": ["name", "score"] },
+                            "report_envelope": { "type": "boolean" },
+                            "format": { "enum": ["json", "pretty_json", "ndjson"] },
+                            "stream_matches": { "type": "boolean" }
+                        }
+                    }
+                }
+            },
+            "source_file": { "type": ["string", "null"] },
+            "matches": {
+                "type": "array",
+                "items": { "$ref": "#/$defs/match" }
+            },
+            "diagnostics": {
+                "type": "object",
+                "required": ["counts", "lines", "inconsistent_matches"],
+                "additionalProperties": false,
+                "properties": {
+                    "counts": {
+                        "type": "object",
+                        "propertyNames": { "$ref": "#/$defs/diagnostic_kind" },
+                        "additionalProperties": { "type": "integer", "minimum": 1 }
+                    },
+                    "lines": {
+                        "type": "array",
+                        "items": { "$ref": "#/$defs/diagnostic" }
+                    },
+                    "inconsistent_matches": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["match", "first_line", "last_line", "counts"],
+                            "additionalProperties": false,
+                            "properties": {
+                                "match": { "type": "string" },
+                                "first_line": { "type": "integer", "minimum": 0 },
+                                "last_line": { "type": "integer", "minimum": 0 },
+                                "counts": {
+                                    "type": "object",
+                                    "propertyNames": { "$ref": "#/$defs/diagnostic_kind" },
+                                    "additionalProperties": { "type": "integer", "minimum": 1 }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+        "$defs": {
+            "diagnostic_kind": {
+                "enum": ["unknown_line", "invalid_encoding", "malformed_event", "unknown_death_cause", "kill_by_unknown_player", "kill_outside_match", "orphaned_event"]
+            },
+            "diagnostic": {
+                "type": "object",
+                "required": ["line_number", "kind", "line"],
+                "additionalProperties": false,
+                "properties": {
+                    "line_number": { "type": "integer", "minimum": 1 },
+                    "kind": { "$ref": "#/$defs/diagnostic_kind" },
+                    "line": { "type": "string" }
+                }
+            },
+            "match": {
+                "type": "object",
+                "minProperties": 1,
+                "maxProperties": 1,
+                "patternProperties": {
+                    format!("^{}_\\d+$", match_key): { "$ref": "#/$defs/match_stats" }
+                },
+                "additionalProperties": false
+            },
+            "match_stats": {
+                "type": "object",
+                "required": [&total_kills_key, &players_key, &kills_key],
+                "additionalProperties": false,
+                "properties": {
+                    total_kills_key.clone(): { "type": "integer", "minimum": 0 },
+                    players_key.clone(): {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "uniqueItems": true
+                    },
+                    kills_key.clone(): {
+                        "type": "object",
+                        "additionalProperties": { "type": "integer" }
+                    },
+                    kill_by_means_key.clone(): {
+                        "type": "object",
+                        "additionalProperties": { "type": "integer", "minimum": 0 }
+                    },
+                    player_stats_key.clone(): {
+                        "type": "object",
+                        "additionalProperties": {
+                            "type": "object",
+                            "required": ["kills", "deaths", "best_streak", "kill_by_means"],
+                            "properties": {
+                                "kills": { "type": "integer", "minimum": 0 },
+                                "deaths": { "type": "integer", "minimum": 0 },
+                                "best_streak": { "type": "integer", "minimum": 0 },
+                                "kill_by_means": {
+                                    "type": "object",
+                                    "additionalProperties": { "type": "integer", "minimum": 0 }
+                                }
+                            }
+                        }
+                    },
+                    rating_deltas_key.clone(): {
+                        "type": "object",
+                        "additionalProperties": { "type": "number" }
+                    },
+                    partial_key.clone(): { "const": true },
+                    provenance_key.clone(): {
+                        "type": "object",
+                        "required": ["file", "first_line", "last_line"],
+                        "properties": {
+                            "file": { "type": "string" },
+                            "first_line": { "type": "integer", "minimum": 1 },
+                            "last_line": { "type": "integer", "minimum": 1 }
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
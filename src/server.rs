@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Router,
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use crate::errors::LogParserError;
+use crate::config::{
+    config::ConfigValue,
+    dynamic_config::{CONFIG, Config, ConfigParameter, ReportFormat}
+};
+use crate::implementation::{
+    log_parser::ConcreteLogParser,
+    log_reader::log_bytes_reader
+};
+use crate::service::LogParser;
+
+pub const REPORTS_CACHE_CAPACITY: usize = 128;
+pub const DEFAULT_MAX_UPLOAD_BYTES: usize = 64 * 1024 * 1024;
+
+struct CachedReport {
+    format: ReportFormat,
+    report: String
+}
+
+#[derive(Clone)]
+pub struct ServerState {
+    config: Config,
+    reports: Arc<Mutex<BTreeMap<u64, CachedReport>>>,
+    next_report_id: Arc<Mutex<u64>>,
+    max_upload_bytes: usize
+}
+
+impl ServerState {
+    /// Snapshots the calling thread config: every uploaded log is parsed with it.
+    pub fn from_current_config() -> Self {
+
+        Self {
+            config: CONFIG.with(|config| config.borrow().clone()),
+            reports: Arc::new(Mutex::new(BTreeMap::new())),
+            next_report_id: Arc::new(Mutex::new(0)),
+            max_upload_bytes: DEFAULT_MAX_UPLOAD_BYTES
+        }
+    }
+
+    /// Largest log accepted by `POST /reports`, bigger uploads are answered with 413.
+    pub fn with_max_upload_bytes(mut self, max_upload_bytes: usize) -> Self {
+        self.max_upload_bytes = max_upload_bytes;
+        return self;
+    }
+
+    fn cache_report(&self, format: ReportFormat, report: String) -> u64 {
+
+        let mut next_report_id = self.next_report_id.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let report_id = *next_report_id;
+        *next_report_id += 1;
+
+        let mut reports = self.reports.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        reports.insert(report_id, CachedReport { format, report });
+
+        while reports.len() > REPORTS_CACHE_CAPACITY {
+            reports.pop_first();
+        }
+
+        return report_id;
+    }
+
+    fn config_format(&self) -> ReportFormat {
+        return ReportFormat::from_str(self.config.get_parameter(ConfigParameter::ReportFormat).to_string().as_str()).unwrap_or_default();
+    }
+}
+
+#[derive(Deserialize)]
+struct ReportQuery {
+    format: Option<String>
+}
+
+pub fn router(state: ServerState) -> Router {
+
+    // Without it axum caps the body at 2 MB, less than a server's daily log.
+    let upload_limit = DefaultBodyLimit::max(state.max_upload_bytes);
+
+    return Router::new()
+        .route("/health", get(health))
+        .route("/reports", post(upload_log).layer(upload_limit))
+        .route("/reports/{id}", get(get_report))
+        .with_state(state);
+}
+
+pub async fn serve(listener: TcpListener, state: ServerState) -> Result<(), LogParserError> {
+    return axum::serve(listener, router(state)).await.map_err(|_e| LogParserError::UnexpectedError);
+}
+
+async fn health() -> impl IntoResponse {
+    return (StatusCode::OK, axum::Json(json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") })));
+}
+
+async fn upload_log(State(state): State<ServerState>, Query(query): Query<ReportQuery>, body: Bytes) -> Response {
+
+    let format = match query.format.as_deref().map(ReportFormat::from_str).unwrap_or(Ok(state.config_format())) {
+        Ok(format) => format,
+        Err(message) => return (StatusCode::BAD_REQUEST, message).into_response()
+    };
+
+    let config = state.config.clone();
+    let log = body.to_vec();
+
+    // Parsing is not Send (CONFIG is thread local), so each upload runs on a blocking thread.
+    let parsed = tokio::task::spawn_blocking(move || {
+
+        config.install();
+
+        CONFIG.with(|thread_config| {
+            thread_config.borrow_mut().set_parameter(ConfigParameter::ReportFormat, ConfigValue::Str(format.to_string()));
+            thread_config.borrow_mut().set_parameter(ConfigParameter::StreamMatches, ConfigValue::Bool(false));
+            thread_config.borrow_mut().set_parameter(ConfigParameter::RatingsFilePath, ConfigValue::OptStr(None));
+        });
+
+        let reader = log_bytes_reader(log)?;
+        let mut log_parser_service = LogParser::new(Box::new(ConcreteLogParser::new()));
+
+        return tokio::runtime::Handle::current().block_on(log_parser_service.parse_log(reader, None));
+    }).await;
+
+    match parsed {
+        Ok(Ok(report)) => {
+            let report_id = state.cache_report(format, report.clone());
+            return (
+                StatusCode::CREATED,
+                [
+                    (header::CONTENT_TYPE, content_type(format).to_string()),
+                    (header::LOCATION, format!("/reports/{}", report_id))
+                ],
+                report
+            ).into_response();
+        },
+        Ok(Err(err)) => (StatusCode::UNPROCESSABLE_ENTITY, err.to_string()).into_response(),
+        Err(_e) => (StatusCode::INTERNAL_SERVER_ERROR, LogParserError::UnexpectedError.to_string()).into_response()
+    }
+}
+
+async fn get_report(State(state): State<ServerState>, Path(id): Path<u64>) -> Response {
+
+    let reports = state.reports.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    match reports.get(&id) {
+        Some(cached) => ([(header::CONTENT_TYPE, content_type(cached.format))], cached.report.clone()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+fn content_type(format: ReportFormat) -> &'static str {
+    match format {
+        ReportFormat::Json | ReportFormat::PrettyJson => "application/json",
+        ReportFormat::Ndjson => "application/x-ndjson"
+    }
+}
@@ -1,8 +1,14 @@
-use crate::interface::{ LogParserCallBack, ILogParser };
+use crate::interface::{ LogParserCallBack, ILogParser, ReportRenderer };
 use crate::errors::LogParserError;
+use crate::implementation::diagnostics::{ParseDiagnostics, ParseMode};
+#[cfg(feature = "metrics")]
+use crate::implementation::metrics::Metrics;
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
 
 use std::future::Future;
 use std::pin::Pin;
+use std::io::{BufRead, Write};
 
 pub struct LogParser {
     log_parser: Box<dyn ILogParser>
@@ -25,7 +31,49 @@ impl LogParser {
         return self.log_parser.register_error_callback(callback);
     }
 
+    pub fn register_match_callback(&mut self, callback: Box<LogParserCallBack>) {
+        return self.log_parser.register_match_callback(callback);
+    }
+
+    pub fn register_match_writer(&mut self, writer: Box<dyn Write + Send>) {
+        return self.log_parser.register_match_writer(writer);
+    }
+
+    pub fn register_renderer(&mut self, renderer: Box<dyn ReportRenderer>) {
+        return self.log_parser.register_renderer(renderer);
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn register_metrics(&mut self, metrics: Arc<Metrics>) {
+        return self.log_parser.register_metrics(metrics);
+    }
+
+    /// Lenient by default: unusable lines only send warning callbacks.
+    pub fn set_parse_mode(&mut self, parse_mode: ParseMode) {
+        return self.log_parser.set_parse_mode(parse_mode);
+    }
+
+    /// Diagnostics of the parsed logs, whether or not the report has an envelope to hold them.
+    /// After `parse_files` only their counts are summed up, the lines are at each file report.
+    pub fn diagnostics(&self) -> &ParseDiagnostics {
+        return self.log_parser.diagnostics();
+    }
+
     pub fn parse_file(&mut self) -> Pin<Box<dyn Future<Output = Result<String, LogParserError>> + '_>> {
         return self.log_parser.parse_file();
     }
+
+    pub fn parse_log(&mut self, reader: Box<dyn BufRead>, source: Option<String>) -> Pin<Box<dyn Future<Output = Result<String, LogParserError>> + '_>> {
+        return self.log_parser.parse_log(reader, source);
+    }
+
+    /// Parses one large log file, splitting it at `InitGame` lines and parsing its matches on
+    /// `workers` threads (all available cores for `0`). The report is the same as `parse_log`.
+    pub fn parse_file_parallel(&mut self, log_file: String, workers: usize) -> Pin<Box<dyn Future<Output = Result<String, LogParserError>> + '_>> {
+        return self.log_parser.parse_file_parallel(log_file, workers);
+    }
+
+    pub fn parse_files(&mut self, pattern: String, concurrent: bool) -> Pin<Box<dyn Future<Output = Result<String, LogParserError>> + '_>> {
+        return self.log_parser.parse_files(pattern, concurrent);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,206 @@
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+use rusqlite::{Connection, OptionalExtension, Transaction, params};
+use sha2::{Digest, Sha256};
+
+use crate::errors::LogParserError;
+use crate::implementation::{
+    log_parser::ConcreteLogParser,
+    match_data::MatchData,
+    match_event::MatchEvent,
+    interner::Name
+};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS log_files (
+        id INTEGER PRIMARY KEY,
+        path TEXT NOT NULL,
+        sha256 TEXT NOT NULL UNIQUE
+    );
+    CREATE TABLE IF NOT EXISTS matches (
+        id INTEGER PRIMARY KEY,
+        log_file_id INTEGER NOT NULL REFERENCES log_files(id),
+        label TEXT NOT NULL,
+        first_line INTEGER NOT NULL,
+        last_line INTEGER NOT NULL,
+        game_type INTEGER,
+        total_kills INTEGER NOT NULL,
+        UNIQUE (log_file_id, first_line)
+    );
+    CREATE TABLE IF NOT EXISTS players (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE
+    );
+    CREATE TABLE IF NOT EXISTS match_players (
+        match_id INTEGER NOT NULL REFERENCES matches(id),
+        player_id INTEGER NOT NULL REFERENCES players(id),
+        score INTEGER NOT NULL,
+        team INTEGER,
+        PRIMARY KEY (match_id, player_id)
+    );
+    CREATE TABLE IF NOT EXISTS kills (
+        id INTEGER PRIMARY KEY,
+        match_id INTEGER NOT NULL REFERENCES matches(id),
+        line INTEGER NOT NULL,
+        time_seconds INTEGER NOT NULL,
+        killer_id INTEGER REFERENCES players(id),
+        victim_id INTEGER NOT NULL REFERENCES players(id),
+        weapon TEXT NOT NULL,
+        UNIQUE (match_id, line)
+    );
+    CREATE TABLE IF NOT EXISTS items (
+        id INTEGER PRIMARY KEY,
+        match_id INTEGER NOT NULL REFERENCES matches(id),
+        line INTEGER NOT NULL,
+        time_seconds INTEGER NOT NULL,
+        player_id INTEGER REFERENCES players(id),
+        item TEXT NOT NULL,
+        UNIQUE (match_id, line)
+    );
+";
+
+/// Rows inserted by an import. Re-importing a log only inserts what is not stored yet.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImportSummary {
+    pub log_file_id: i64,
+    pub matches: usize,
+    pub kills: usize,
+    pub items: usize
+}
+
+/// Normalized SQLite storage of parsed matches, keyed by log file hash and line offset.
+pub struct SqliteStore {
+    connection: Connection
+}
+
+impl SqliteStore {
+    pub fn open(path: &str) -> Result<Self, LogParserError> {
+        let connection = Connection::open(path).map_err(|_e| LogParserError::StorageError)?;
+        return Self::with_connection(connection);
+    }
+
+    pub fn open_in_memory() -> Result<Self, LogParserError> {
+        let connection = Connection::open_in_memory().map_err(|_e| LogParserError::StorageError)?;
+        return Self::with_connection(connection);
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, LogParserError> {
+        connection.execute_batch(SCHEMA).map_err(|_e| LogParserError::StorageError)?;
+        return Ok(Self { connection });
+    }
+
+    pub fn connection(&self) -> &Connection {
+        return &self.connection;
+    }
+
+    pub async fn import_file(&mut self, log_file: &str) -> Result<ImportSummary, LogParserError> {
+
+        let sha256 = file_sha256(log_file)?;
+
+        let mut log_parser = ConcreteLogParser::event_recording_parser(String::from(log_file));
+        let matches = log_parser.parse_log_file(log_file).await?.matches;
+
+        let transaction = self.connection.transaction().map_err(|_e| LogParserError::StorageError)?;
+
+        transaction.execute("INSERT OR IGNORE INTO log_files (path, sha256) VALUES (?1, ?2)", params![log_file, sha256])
+            .map_err(|_e| LogParserError::StorageError)?;
+
+        let log_file_id: i64 = transaction.query_row("SELECT id FROM log_files WHERE sha256 = ?1", params![sha256], |row| row.get(0))
+            .map_err(|_e| LogParserError::StorageError)?;
+
+        let mut summary = ImportSummary { log_file_id, ..Default::default() };
+
+        for match_data in matches.iter() {
+            insert_match(&transaction, log_file_id, match_data, &mut summary).map_err(|_e| LogParserError::StorageError)?;
+        }
+
+        transaction.commit().map_err(|_e| LogParserError::StorageError)?;
+
+        return Ok(summary);
+    }
+}
+
+fn file_sha256(log_file: &str) -> Result<String, LogParserError> {
+
+    let mut reader = BufReader::new(File::open(log_file).map_err(|_e| LogParserError::ReadFileError)?);
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = reader.read(&mut buffer).map_err(|_e| LogParserError::ReadFileError)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    return Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect());
+}
+
+fn player_id(transaction: &Transaction, name: &str) -> rusqlite::Result<i64> {
+    transaction.execute("INSERT OR IGNORE INTO players (name) VALUES (?1)", params![name])?;
+    return transaction.query_row("SELECT id FROM players WHERE name = ?1", params![name], |row| row.get(0));
+}
+
+fn insert_match(transaction: &Transaction, log_file_id: i64, match_data: &MatchData, summary: &mut ImportSummary) -> rusqlite::Result<()> {
+
+    let existing_match_id: Option<i64> = transaction.query_row(
+        "SELECT id FROM matches WHERE log_file_id = ?1 AND first_line = ?2",
+        params![log_file_id, match_data.first_line as i64],
+        |row| row.get(0)
+    ).optional()?;
+
+    let match_id = match existing_match_id {
+        Some(match_id) => match_id,
+        None => {
+            transaction.execute(
+                "INSERT INTO matches (log_file_id, label, first_line, last_line, game_type, total_kills) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![log_file_id, match_data.game_match, match_data.first_line as i64, match_data.last_line as i64, match_data.game_type, match_data.total_kills]
+            )?;
+            summary.matches += 1;
+            transaction.last_insert_rowid()
+        }
+    };
+
+    let mut players: BTreeSet<&Name> = match_data.players.iter().collect();
+    players.extend(match_data.kills.keys());
+    players.extend(match_data.player_stats.keys());
+
+    for player in players {
+        let player_id = player_id(transaction, player)?;
+        transaction.execute(
+            "INSERT OR IGNORE INTO match_players (match_id, player_id, score, team) VALUES (?1, ?2, ?3, ?4)",
+            params![match_id, player_id, match_data.kills.get(player).unwrap_or(&0), match_data.teams.get(player)]
+        )?;
+    }
+
+    for event in match_data.events.iter() {
+        match event {
+            MatchEvent::Kill { line, time_seconds, killer, victim, weapon } => {
+                let killer_id = match killer {
+                    Some(killer) => Some(player_id(transaction, killer)?),
+                    None => None
+                };
+                let victim_id = player_id(transaction, victim)?;
+                summary.kills += transaction.execute(
+                    "INSERT OR IGNORE INTO kills (match_id, line, time_seconds, killer_id, victim_id, weapon) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![match_id, *line as i64, time_seconds, killer_id, victim_id, weapon]
+                )?;
+            },
+            MatchEvent::Item { line, time_seconds, player, item } => {
+                let player_id = match player {
+                    Some(player) => Some(player_id(transaction, player)?),
+                    None => None
+                };
+                summary.items += transaction.execute(
+                    "INSERT OR IGNORE INTO items (match_id, line, time_seconds, player_id, item) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![match_id, *line as i64, time_seconds, player_id, item]
+                )?;
+            }
+        }
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,71 @@
+#![allow(clippy::needless_return)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+const BINARY: &str = env!("CARGO_BIN_EXE_quake-log-parser");
+
+fn temp_log(name: &str, content: &[u8]) -> std::path::PathBuf {
+    let log_path = std::env::temp_dir().join(format!("quake_log_parser_cli_{}_{}.log", name, std::process::id()));
+    std::fs::write(&log_path, content).unwrap();
+    return log_path;
+}
+
+#[test]
+fn tail_prints_a_match_once_it_shuts_down() {
+
+    let log_path = temp_log("tail", b"");
+
+    let mut tail = Command::new(BINARY)
+        .args(["tail", log_path.to_str().unwrap(), "--from-start", "--poll-interval", "20"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    let stdout = tail.stdout.take().unwrap();
+
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut log_file = std::fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+    log_file.write_all(b"  0:00 InitGame: \\g_gametype\\0\\\n").unwrap();
+    log_file.write_all(b"  0:01 ClientUserinfoChanged: 2 n\\Isgalamido\\t\\0\\\n").unwrap();
+    log_file.write_all(b"  0:02 Kill: 1022 2 22: <world> killed Isgalamido by MOD_FALLING\n").unwrap();
+    log_file.write_all(b"  0:03 ShutdownGame:\n").unwrap();
+
+    // The log is still followed: the match must show up with no next InitGame nor end of file.
+    let streamed_match = receiver.recv_timeout(Duration::from_secs(10));
+
+    tail.kill().unwrap();
+    tail.wait().unwrap();
+    std::fs::remove_file(&log_path).unwrap();
+
+    let streamed_match: serde_json::Value = serde_json::from_str(&streamed_match.expect("tail printed no match")).unwrap();
+    assert_eq!(streamed_match["game_0"]["total_kills"], 1);
+    assert_eq!(streamed_match["game_0"]["players"], serde_json::json!(["Isgalamido"]));
+}
+
+#[test]
+fn validate_reports_undecodable_lines_as_unknown() {
+
+    let log_path = temp_log("validate", b"  0:00 InitGame: \\g_gametype\\0\\\n  0:01 say: Isgalamido: \xff\xfe\n  0:02 ShutdownGame:\n");
+
+    let output = Command::new(BINARY).args(["validate", log_path.to_str().unwrap()]).output().unwrap();
+    std::fs::remove_file(&log_path).unwrap();
+
+    let validation: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(validation["lines_checked"], 3);
+    assert_eq!(validation["unknown_lines_count"], 1);
+    assert_eq!(validation["unknown_lines"][0]["line"], 2);
+    assert_eq!(validation["unknown_lines"][0]["text"], "  0:01 say: Isgalamido: \u{fffd}\u{fffd}");
+}